@@ -1,5 +1,231 @@
+use std::{env, fs, path::{Path, PathBuf}};
+
+use rusttype::{point, Font, Scale};
+
+#[path = "src/scene_compiler.rs"]
+mod scene_compiler;
+
+use scene_compiler::{compile_scene, SceneAssets};
+
+// ---------------------------------------------------
+// Glyph atlas
+// ---------------------------------------------------
+// Scientia-Black is rasterized here once, so the device only copies alpha values.
+// Override the defaults with e.g. GLYPH_ATLAS_SIZES="16,24" GLYPH_ATLAS_CHARS="0x20-0x7e,0xb0"
+
+const ATLAS_FONT: &str = "src/assets/Scientia-Black.ttf";
+// Pixel heights baked into the atlas, text asks for the closest one
+const ATLAS_SIZES: &[u32] = &[12, 16, 24, 32];
+// Printable ASCII plus the degree sign
+const ATLAS_CHARS: &[(u32, u32)] = &[(0x20, 0x7e), (0xb0, 0xb0)];
+
+const ATLAS_MAGIC: &[u8; 4] = b"GLYA";
+const ATLAS_VERSION: u8 = 1;
+const SIZE_HEADER_LEN: usize = 20;
+
 fn main() {
-    if std::env::var("CARGO_FEATURE_SIMULATOR").is_err() {
+    if env::var("CARGO_FEATURE_SIMULATOR").is_err() {
         println!("cargo:rustc-link-arg=-Tlinkall.x");
     }
+
+    generate_glyph_atlas();
+    generate_scenes();
+}
+
+/*
+ * Layout of glyph_atlas.bin, all little-endian:
+ *  header: magic "GLYA", version u8, size count u8
+ *  per size (20 bytes): pixel size u8, ascent i8, descent i8, line gap i8,
+ *      glyph count u16, kerning count u16, glyph/kerning/bitmap table offsets u32
+ *  glyph (13 bytes): codepoint u32, bitmap offset u32, advance u8,
+ *      width u8, height u8, x offset i8, y offset i8 (from the pen on the baseline)
+ *  kerning (5 bytes): left glyph index u16, right glyph index u16, adjustment i8
+ *  bitmaps: 4 bits of coverage per pixel, row-major, high nibble first
+ */
+fn generate_glyph_atlas() {
+    println!("cargo:rerun-if-changed={}", ATLAS_FONT);
+    println!("cargo:rerun-if-env-changed=GLYPH_ATLAS_SIZES");
+    println!("cargo:rerun-if-env-changed=GLYPH_ATLAS_CHARS");
+
+    let font_data = fs::read(ATLAS_FONT).expect("glyph atlas: font file missing");
+    let font = Font::try_from_vec(font_data).expect("glyph atlas: font file isn't a valid TTF");
+
+    let sizes = match env::var("GLYPH_ATLAS_SIZES") {
+        Ok(list) => list.split(',').map(|size| size.trim().parse().expect("GLYPH_ATLAS_SIZES: bad size")).collect(),
+        Err(_) => ATLAS_SIZES.to_vec(),
+    };
+    let ranges = match env::var("GLYPH_ATLAS_CHARS") {
+        Ok(list) => list.split(',').map(parse_char_range).collect(),
+        Err(_) => ATLAS_CHARS.to_vec(),
+    };
+
+    let mut chars: Vec<char> = ranges
+        .iter()
+        .flat_map(|&(first, last)| first..=last)
+        .filter_map(char::from_u32)
+        // Skip anything the font doesn't have, it would only bake the .notdef box
+        .filter(|&c| c == ' ' || font.glyph(c).id().0 != 0)
+        .collect();
+    chars.sort_unstable();
+    chars.dedup();
+
+    let mut tables: Vec<Vec<u8>> = Vec::new();
+    let mut headers: Vec<[u8; SIZE_HEADER_LEN]> = Vec::new();
+    let mut offset = 6 + sizes.len() * SIZE_HEADER_LEN;
+
+    for &size in &sizes {
+        assert!(size > 0 && size < 128, "glyph atlas: size {} out of range", size);
+        let (header, table) = rasterize_size(&font, size, &chars, offset);
+        offset += table.len();
+        headers.push(header);
+        tables.push(table);
+    }
+
+    let mut atlas = Vec::with_capacity(offset);
+    atlas.extend_from_slice(ATLAS_MAGIC);
+    atlas.push(ATLAS_VERSION);
+    atlas.push(sizes.len() as u8);
+    headers.iter().for_each(|header| atlas.extend_from_slice(header));
+    tables.iter().for_each(|table| atlas.extend_from_slice(table));
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("glyph_atlas.bin"), atlas).unwrap();
+}
+
+// "0x20-0x7e" or a single "0xb0", decimal works too
+fn parse_char_range(range: &str) -> (u32, u32) {
+    let parse = |value: &str| {
+        let value = value.trim();
+        match value.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => value.parse(),
+        }
+        .expect("GLYPH_ATLAS_CHARS: bad character code")
+    };
+
+    match range.split_once('-') {
+        Some((first, last)) => (parse(first), parse(last)),
+        None => (parse(range), parse(range)),
+    }
+}
+
+// Glyph, kerning and bitmap tables for one pixel size, 'base' is where they land in the file
+fn rasterize_size(font: &Font, size: u32, chars: &[char], base: usize) -> ([u8; SIZE_HEADER_LEN], Vec<u8>) {
+    let scale = Scale::uniform(size as f32);
+    let metrics = font.v_metrics(scale);
+
+    let mut glyphs = Vec::new();
+    let mut bitmaps = Vec::new();
+
+    for &c in chars {
+        let scaled = font.glyph(c).scaled(scale);
+        let advance = scaled.h_metrics().advance_width.round().clamp(0.0, 255.0) as u8;
+        let positioned = scaled.positioned(point(0.0, 0.0));
+
+        let bitmap_offset = bitmaps.len() as u32;
+        let (width, height, x_offset, y_offset) = match positioned.pixel_bounding_box() {
+            Some(bounds) => {
+                let (width, height) = (bounds.width() as u32, bounds.height() as u32);
+                let mut coverage = vec![0u8; (width * height) as usize];
+                positioned.draw(|x, y, value| {
+                    coverage[(y * width + x) as usize] = (value * 15.0).round().min(15.0) as u8;
+                });
+                bitmaps.extend(coverage.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0)));
+                (width as u8, height as u8, bounds.min.x as i8, bounds.min.y as i8)
+            }
+            // Whitespace only moves the pen
+            None => (0, 0, 0, 0),
+        };
+
+        glyphs.extend_from_slice(&(c as u32).to_le_bytes());
+        glyphs.extend_from_slice(&bitmap_offset.to_le_bytes());
+        glyphs.extend_from_slice(&[advance, width, height, x_offset as u8, y_offset as u8]);
+    }
+
+    // Only pairs that still move the pen after rounding to whole pixels
+    let mut kerning = Vec::new();
+    let mut kern_count = 0u16;
+    for (left_index, &left) in chars.iter().enumerate() {
+        for (right_index, &right) in chars.iter().enumerate() {
+            let amount = font.pair_kerning(scale, left, right).round();
+            if amount == 0.0 {
+                continue;
+            }
+            kerning.extend_from_slice(&(left_index as u16).to_le_bytes());
+            kerning.extend_from_slice(&(right_index as u16).to_le_bytes());
+            kerning.push(amount.clamp(-128.0, 127.0) as i8 as u8);
+            kern_count += 1;
+        }
+    }
+
+    let glyph_offset = base as u32;
+    let kerning_offset = glyph_offset + glyphs.len() as u32;
+    let bitmap_offset = kerning_offset + kerning.len() as u32;
+
+    let mut header = [0u8; SIZE_HEADER_LEN];
+    header[0] = size as u8;
+    header[1] = metrics.ascent.round() as i8 as u8;
+    header[2] = metrics.descent.round() as i8 as u8;
+    header[3] = metrics.line_gap.round() as i8 as u8;
+    header[4..6].copy_from_slice(&(chars.len() as u16).to_le_bytes());
+    header[6..8].copy_from_slice(&kern_count.to_le_bytes());
+    header[8..12].copy_from_slice(&glyph_offset.to_le_bytes());
+    header[12..16].copy_from_slice(&kerning_offset.to_le_bytes());
+    header[16..20].copy_from_slice(&bitmap_offset.to_le_bytes());
+
+    let mut table = glyphs;
+    table.extend(kerning);
+    table.extend(bitmaps);
+    (header, table)
+}
+
+// ---------------------------------------------------
+// Scenes
+// ---------------------------------------------------
+// Every src/assets/scenes/<name>.scene becomes `pub const <NAME>_SCENE: SceneData`
+// in OUT_DIR/scenes.rs, which src/scenes.rs includes. The file format and its checks
+// are in src/scene_compiler.rs.
+
+const SCENES_DIR: &str = "src/assets/scenes";
+
+fn generate_scenes() {
+    println!("cargo:rerun-if-changed={}", SCENES_DIR);
+    println!("cargo:rerun-if-changed=src/scene_compiler.rs");
+    println!("cargo:rerun-if-changed=src/limits.rs");
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(SCENES_DIR)
+        .expect("scenes: src/assets/scenes missing")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "scene"))
+        .collect();
+    paths.sort();
+
+    let mut assets = SceneAssets::default();
+    let mut scenes = String::new();
+    let mut errors = Vec::new();
+
+    for path in &paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let source = fs::read_to_string(path).unwrap();
+        match compile_scene(path, &source, &mut assets) {
+            Ok(code) => scenes.push_str(&code),
+            Err(mut scene_errors) => errors.append(&mut scene_errors),
+        }
+    }
+
+    if !errors.is_empty() {
+        errors.iter().for_each(|error| eprintln!("error: {}", error));
+        std::process::exit(1);
+    }
+
+    let mut out = String::from("// Generated by build.rs from src/assets/scenes\n\n");
+    out.push_str("#[allow(unused_imports)]\n");
+    out.push_str("use embedded_graphics::{pixelcolor::Rgb565, prelude::{Point, Size}, primitives::Rectangle, text::Alignment};\n");
+    out.push_str("#[allow(unused_imports)]\n");
+    out.push_str("use crate::{animations::{Animation, AnimationIterator, AnimationMetadata}, clickable::ClickableElement, duration_editor::DurationEditor, progress::{ProgressBar, ProgressRing}, scenes_util::{DigitsElement, ElementId, ElementList, ImageData, Scene, SceneData, SceneElement, UIType}, stats::BarChartElement, text_box::{LabelElement, TextElement}, transitions::Transition};\n\n");
+    out.push_str(&assets.code);
+    out.push_str(&scenes);
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("scenes.rs"), out).unwrap();
 }
@@ -1,6 +1,5 @@
-use embedded_graphics::{prelude::Point, primitives::{line::Line, Rectangle}};
+use embedded_graphics::{prelude::Point, primitives::Rectangle};
 use embedded_graphics::prelude::*;
-use embedded_graphics::geometry::AnchorPoint;
 use crate::constants::MAX_ANIMATIONS;
 
 #[derive(Debug, Copy, Clone)]
@@ -104,28 +103,29 @@ impl AnimationMetadata {
     }
 }
 
+// Frames it takes the focus highlight to slide between two elements
+pub const CURSOR_FRAMES: usize = 8;
+
+/*
+ * Focus highlight sliding from one element's rectangle to another's,
+ * position and size are interpolated together
+ */
 #[derive(Debug, Clone, Copy)]
 pub struct CursorMove {
-    pub start: Point,
-    pub end: Point,
+    pub start: Rectangle,
+    pub end: Rectangle,
     pub cursor_rect: Rectangle,
-    pub path: Line,
     pub frame_count: usize,
     pub frame_index: usize
 }
 
 impl CursorMove {
-    pub fn initialize(start_pos: Point, end_pos: Point) -> Self {
-        let cursor = Rectangle::with_corners(Point::zero(), start_pos);
-        let path = Line::new(start_pos, end_pos);
-        let frame_count = path.points().count();
-
+    pub fn initialize(start: Rectangle, end: Rectangle) -> Self {
         Self {
-            start: start_pos,
-            end: end_pos,
-            cursor_rect: cursor,
-            path,
-            frame_count,
+            start,
+            end,
+            cursor_rect: start,
+            frame_count: CURSOR_FRAMES,
             frame_index: 0
         }
     }
@@ -135,16 +135,8 @@ impl CursorMove {
             return None;
         };
 
-        let position = self.path.points().nth(self.frame_index)?;
-
-        let x = position.x as u32;
-        let y = position.y as u32;
-        self.cursor_rect = self.cursor_rect
-            .resized(
-                Size::new(x, y), 
-                AnchorPoint::TopLeft);
-
         self.frame_index += 1;
+        self.cursor_rect = self.rect_at(self.frame_index);
         Some(FrameType::Rectangle(self.cursor_rect))
     }
 
@@ -155,21 +147,21 @@ impl CursorMove {
         FrameType::Rectangle(self.cursor_rect)
     }
 
-    pub fn get_frame(&mut self) -> FrameType {
-        let mut frame = FrameType::Empty;
-        if self.frame_index >= self.frame_count {
-            let position = self.path.points()
-                .nth(self.frame_index as usize)
-                .unwrap();
-
-            let x = position.x as u32;
-            let y = position.y as u32;
-            self.cursor_rect = self.cursor_rect.resized(Size::new(x, y), AnchorPoint::TopLeft);
-            
-            self.frame_index += 1;
-            frame = FrameType::Rectangle(self.cursor_rect.clone())
-        }
-        frame
+    // Last frame lands exactly on the end rectangle
+    fn rect_at(&self, frame: usize) -> Rectangle {
+        let lerp = |from: i32, to: i32| {
+            from + (to - from) * frame as i32 / self.frame_count as i32
+        };
+
+        let top_left = Point::new(
+            lerp(self.start.top_left.x, self.end.top_left.x),
+            lerp(self.start.top_left.y, self.end.top_left.y)
+        );
+        let size = Size::new(
+            lerp(self.start.size.width as i32, self.end.size.width as i32) as u32,
+            lerp(self.start.size.height as i32, self.end.size.height as i32) as u32
+        );
+        Rectangle::new(top_left, size)
     }
 }
 
@@ -4,10 +4,12 @@
 use embassy_executor::Spawner;
 use esp_alloc::HeapStats;
 use esp_backtrace as _;
-use esp_hal::{clock::CpuClock, gpio::{Input, InputConfig, Pull}, interrupt::software::SoftwareInterruptControl, peripherals::PSRAM, psram::psram_raw_parts, system::Stack, timer::timg::TimerGroup};
+use esp_hal::{clock::CpuClock, gpio::{Input, InputConfig, Pull}, interrupt::software::SoftwareInterruptControl, system::Stack, timer::timg::TimerGroup};
 use static_cell::StaticCell;
-use timetool_v2::{button::Button, clock::{DoubleTimerSession, SessionNotifier}, payloads::SessionState, tft::{SpiPins, TFT}};
-use timetool_v2::constants::PSRAM_ALLOCATOR;
+use embassy_sync::mutex::Mutex;
+use esp_storage::FlashStorage;
+use timetool_v2::{button::Button, clock::{DoubleTimerSession, SessionNotifier}, encoder::{AccelerationCurve, RotaryEncoder}, input::{input_loop, InputChannel, InputDevices}, history::{FlashLogStorage, HistoryLog, SessionHistory}, payloads::SessionState, tft::{SpiPins, TFT}};
+use timetool_v2::constants::{HISTORY_FLASH_OFFSET, HISTORY_SECTORS, PSRAM_ALLOCATOR};
 esp_bootloader_esp_idf::esp_app_desc!();
 
 
@@ -21,7 +23,6 @@ async fn main(spawner: Spawner) {
 
     static APP_CORE_STACK: StaticCell<Stack<8192>> = StaticCell::new();
     let app_core_stack = APP_CORE_STACK.init(Stack::new());
-    // init_psram_heap(&peripherals.PSRAM);
 
     esp_alloc::psram_allocator!(peripherals.PSRAM, esp_hal::psram);
 
@@ -54,27 +55,30 @@ async fn main(spawner: Spawner) {
 
      let config = InputConfig::default().with_pull(Pull::Down);
      let input = Input::new(peripherals.GPIO16, config);
-     let mut button = Button::new(input);
+     let button = Button::new(input);
+
+     let flash = FlashLogStorage::new(FlashStorage::new(peripherals.FLASH), HISTORY_FLASH_OFFSET, HISTORY_SECTORS);
+     static HISTORY: StaticCell<SessionHistory> = StaticCell::new();
+     let history = HISTORY.init(Mutex::new(HistoryLog::mount_or_format(flash)));
 
      let mut state = SessionState::default();
      static SESSION_NOTIFIER: SessionNotifier = DoubleTimerSession::notifier();
-     let mut session = DoubleTimerSession::new(tft, spawner, &SESSION_NOTIFIER).unwrap();
+     let mut session = DoubleTimerSession::new(tft, spawner, &SESSION_NOTIFIER, history).unwrap();
 
-    // TODO: Spawn some tasks
-    let _ = spawner;
+     // Button, encoder and the encoder's push switch all end up in one UIAction channel
+     let encoder_config = InputConfig::default().with_pull(Pull::Up);
+     let encoder = RotaryEncoder::new(
+         Input::new(peripherals.GPIO17, encoder_config),
+         Input::new(peripherals.GPIO18, encoder_config)
+     );
+     let encoder_switch = Button::new(Input::new(peripherals.GPIO21, config));
+     static INPUTS: InputChannel = InputChannel::new();
+     let (_, _, _, hold_notifier, _, multi_tap) = &SESSION_NOTIFIER;
+     let devices = InputDevices { button, encoder, encoder_switch, acceleration: AccelerationCurve::default() };
+     spawner.spawn(input_loop(devices, &INPUTS, hold_notifier, multi_tap)).unwrap();
+     let mut inputs = INPUTS.receiver();
 
     loop {
-        esp_println::println!("im in da embussy :3");
-        state = state.execute(&mut session, &mut button).await;
-    }
-}
-
-fn init_psram_heap(psram: &PSRAM<'_>) {
-    unsafe {
-        let (start, size) = psram_raw_parts(psram);
-        PSRAM_ALLOCATOR.add_region(esp_alloc::HeapRegion::new(
-                start,
-                size,
-                esp_alloc::MemoryCapability::External.into()));
+        state = state.execute(&mut session, &mut inputs).await;
     }
 }
@@ -1,12 +1,66 @@
-use embedded_graphics::pixelcolor::Rgb565;
+use embassy_executor::Spawner;
+use embassy_futures::select::select;
+use embassy_sync::mutex::Mutex;
+use embassy_time::{Duration, Instant, Ticker, Timer};
 use embedded_graphics_simulator::{
+    sdl2::Keycode,
     OutputSettingsBuilder,
     SimulatorEvent,
     Window,
 };
-use timetool_v2::tft::TFT;
+use timetool_v2::{
+    button::{hold_progress, HoldEvent, PressDuration, HOLD_START, LONG_PRESS},
+    clock::{DoubleTimerSession, SessionNotifier},
+    encoder::{AccelerationCurve, Accelerator, RotationDirection},
+    constants::{FRAME_RATE, HISTORY_FILE, HISTORY_SECTORS},
+    history::{FileLogStorage, HistoryLog, SessionHistory},
+    input::InputChannel,
+    payloads::{Packet, SessionState},
+    render_display::{render_step, HoldNotifier, OverlayNotifier, TFTNotifier},
+    scenes_util::{Turn, UIAction},
+    tft::TFT,
+};
+
+// How often SDL events are drained while the renderer is idle
+const EVENT_POLL: Duration = Duration::from_millis(16);
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    static SESSION_NOTIFIER: SessionNotifier = DoubleTimerSession::notifier();
+    // Keyboard standing in for the button and encoder
+    static INPUTS: InputChannel = InputChannel::new();
+
+    let storage = FileLogStorage::open(HISTORY_FILE, HISTORY_SECTORS).unwrap();
+    let mut log = HistoryLog::mount_or_format(storage);
+    // The desktop has a real clock, so history lines up with unix time
+    let unix_now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    log.set_epoch((unix_now - Instant::now().as_secs()) as u32);
+    let history: &'static SessionHistory = Box::leak(Box::new(Mutex::new(log)));
+
+    let (_, tft_notifier, _, hold_notifier, overlay_notifier, _) = &SESSION_NOTIFIER;
+    let mut session = DoubleTimerSession::without_renderer(spawner, &SESSION_NOTIFIER, history).unwrap();
+    spawner.spawn(window_loop(tft_notifier, hold_notifier, overlay_notifier, &INPUTS)).unwrap();
 
-fn main() {
+    println!("Space: short press | Enter: press, hold for long press | D: double click | T: triple tap | Left/Right: move through menus, adjust timer | Escape: quit");
+
+    let mut inputs = INPUTS.receiver();
+    let mut state = SessionState::default();
+    loop {
+        state = state.execute(&mut session, &mut inputs).await;
+    }
+}
+
+// Stands in for render_display::render_loop: owns the TFT and the SDL window
+#[embassy_executor::task]
+async fn window_loop(
+    notifier: &'static TFTNotifier,
+    hold_notifier: &'static HoldNotifier,
+    overlay_notifier: &'static OverlayNotifier,
+    inputs: &'static InputChannel
+) {
     let mut tft = TFT::new_simulator();
 
     let output_settings = OutputSettingsBuilder::new()
@@ -14,25 +68,70 @@ fn main() {
         .build();
     let mut window = Window::new("Timetool Simulator", &output_settings);
 
-    window.update(&tft.display);
+    let packet = Packet::default();
+    tft.handle_payload(&packet);
+
+    let mut frame_ticker = Ticker::every(Duration::from_hz(FRAME_RATE));
+    let mut accelerator = Accelerator::new(AccelerationCurve::default());
+    // Enter behaves like the hardware button: when it went down, if it's still held
+    let mut held_since: Option<Instant> = None;
+
+    loop {
+        // Render exactly like the hardware does, but wake up regularly to keep SDL responsive
+        select(
+            render_step(&mut tft, notifier, hold_notifier, overlay_notifier, &mut frame_ticker),
+            Timer::after(EVENT_POLL)
+        ).await;
+        window.update(&tft.display);
+
+        if let Some(since) = held_since {
+            let held = Instant::now() - since;
+            if held >= LONG_PRESS {
+                held_since = None;
+                hold_notifier.signal(HoldEvent::Completed);
+                let _ = inputs.try_send(UIAction::from(PressDuration::Long));
+            } else if held >= HOLD_START {
+                hold_notifier.signal(HoldEvent::Repeat(hold_progress(held)));
+            }
+        }
 
-    'running: loop {
         for event in window.events() {
             match event {
-                SimulatorEvent::Quit => break 'running,
-                SimulatorEvent::KeyDown { keycode, .. } => {
-                    println!("Key pressed: {:?}", keycode);
+                SimulatorEvent::Quit => std::process::exit(0),
+                // Arrow keys stand in for the rotary encoder, holding one down spins it fast
+                SimulatorEvent::KeyDown { keycode: keycode @ (Keycode::Left | Keycode::Right), .. } => {
+                    let detent = if keycode == Keycode::Right { 1 } else { -1 };
+                    let accelerated = accelerator.apply(detent, Instant::now());
+                    if let Some(direction) = RotationDirection::from_steps(detent) {
+                        let _ = inputs.try_send(direction.action(Turn::new(detent, accelerated)));
+                    }
+                }
+                SimulatorEvent::KeyDown { keycode: Keycode::Return, repeat: false, .. } => {
+                    held_since = Some(Instant::now());
+                }
+                SimulatorEvent::KeyUp { keycode: Keycode::Return, .. } => {
+                    // Released before it became a long press
+                    if let Some(since) = held_since.take() {
+                        if Instant::now() - since >= HOLD_START {
+                            hold_notifier.signal(HoldEvent::Cancelled);
+                        }
+                        let _ = inputs.try_send(UIAction::from(PressDuration::Short));
+                    }
+                }
+                SimulatorEvent::KeyDown { keycode, repeat: false, .. } => {
+                    let press = match keycode {
+                        Keycode::Space => PressDuration::Short,
+                        Keycode::D => PressDuration::DoubleClick,
+                        Keycode::T => PressDuration::MultiTap(3),
+                        Keycode::Escape => std::process::exit(0),
+                        _ => continue
+                    };
+                    let action = UIAction::from(press);
+                    println!("Key pressed: {:?} -> {:?}", keycode, action);
+                    let _ = inputs.try_send(action);
                 }
                 _ => {}
             }
         }
-
-        if tft.playing_animation {
-            tft.render_next_frame();
-            window.update(&tft.display);
-
-            // Throttle to ~15 FPS to match hardware
-            std::thread::sleep(std::time::Duration::from_millis(1000 / 15));
-        }
     }
 }
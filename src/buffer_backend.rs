@@ -68,12 +68,10 @@ impl BufferData {
         }
 
         // Try to merge area with an existing dirty region
-        for region in self.dirty_regions.iter_mut().take(self.dirty_count) {
-            if let Some(existing) = region {
-                if let Some(merged) = Self::try_merge(*existing, rect) {
-                    *existing = merged;
-                    return;
-                }
+        for existing in self.dirty_regions.iter_mut().take(self.dirty_count).flatten() {
+            if let Some(merged) = Self::try_merge(*existing, rect) {
+                *existing = merged;
+                return;
             }
         }
 
@@ -141,6 +139,10 @@ impl BufferData {
         (y * DISPLAY_WIDTH + x) as usize
     }
 
+    pub fn pixel(&self, x: u32, y: u32) -> Rgb565 {
+        self.buffer[Self::pixel_index(x, y)]
+    }
+
     // DMA Transfer helper
     // Get raw slice for a rectangular region
     // Returns row-by-row slices for the region
@@ -1,59 +1,245 @@
 use embassy_futures::select::{select, Either};
-use esp_hal::gpio::Input;
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer};
+use embedded_hal::digital::InputPin;
+use embedded_hal_async::digital::Wait;
 
-pub struct Button<'a>(Input<'a>);
+/*
+ * Active-high push button on any pin that can be read and awaited.
+ * Pin errors are ignored, the esp-hal Input can't fail anyway.
+ */
+pub struct Button<P> {
+    input: P,
+    // How long after a release another press still counts as part of the same gesture
+    tap_window: Duration
+}
 
 const DEBOUNCE_DELAY: Duration = Duration::from_millis(50);
-const LONG_PRESS: Duration = Duration::from_millis(1000);
+pub const LONG_PRESS: Duration = Duration::from_millis(1000);
+// Holds shorter than this are plain presses and don't show any progress
+pub const HOLD_START: Duration = Duration::from_millis(200);
+const HOLD_TICK: Duration = Duration::from_millis(50);
+pub const DEFAULT_TAP_WINDOW: Duration = Duration::from_millis(300);
+
 
+impl<P: InputPin + Wait> Button<P> {
+    pub const fn new(button: P) -> Self {
+        Self { input: button, tap_window: DEFAULT_TAP_WINDOW }
+    }
 
-impl<'a> Button<'a> {
-    pub const fn new(button: Input<'a>) -> Self {
-        Self(button)
+    // A zero window turns off multi-tap detection, short presses then resolve on release
+    pub fn with_tap_window(self, tap_window: Duration) -> Self {
+        Self { tap_window, ..self }
     }
 
     #[inline]
     async fn wait_for_button_up(&mut self) -> &mut Self {
-        self.0.wait_for_low().await;
-        esp_println::println!("waited for low");
+        let _ = self.input.wait_for_low().await;
         self
     }
 
     #[inline]
     async fn wait_for_button_down(&mut self) -> &mut Self {
-        self.0.wait_for_high().await;
+        let _ = self.input.wait_for_high().await;
         self
     }
 
     pub async fn press_duration(&mut self) -> PressDuration {
+        self.press_with_hold(|_| {}, || true).await
+    }
+
+    /*
+     * Like press_duration, reporting how far a held press is from becoming a long one.
+     * 'multi_tap' is asked on release whether to wait for more taps at all.
+     */
+    pub async fn press_with_hold(&mut self, mut on_hold: impl FnMut(HoldEvent), multi_tap: impl Fn() -> bool) -> PressDuration {
         self.wait_for_button_up().await;
         Timer::after(DEBOUNCE_DELAY).await;
         self.wait_for_button_down().await;
         Timer::after(DEBOUNCE_DELAY).await;
-        let press_duration = 
-            match select(self.wait_for_button_up(), Timer::after(LONG_PRESS)).await {
-                Either::First(_) => { 
-                    esp_println::println!("Short Press!");
-                    PressDuration::Short
-                },
-                Either::Second(()) => { 
-                    esp_println::println!("Long Press!");
-                    PressDuration::Long
+
+        let pressed_at = Instant::now();
+        let mut holding = false;
+        loop {
+            let held = Instant::now() - pressed_at;
+            if held >= LONG_PRESS {
+                on_hold(HoldEvent::Completed);
+                debug_println!("Long Press!");
+                return PressDuration::Long;
+            }
+            if held >= HOLD_START {
+                if !holding {
+                    holding = true;
+                    on_hold(HoldEvent::Started);
                 }
-            };
+                on_hold(HoldEvent::Repeat(hold_progress(held)));
+            }
+
+            let tick = HOLD_TICK.min(LONG_PRESS - held);
+            if let Either::First(_) = select(self.wait_for_button_up(), Timer::after(tick)).await {
+                break;
+            }
+        }
+        if holding {
+            on_hold(HoldEvent::Cancelled);
+        }
+
+        // Keep counting while the next press starts within the tap window
+        let tap_window = if multi_tap() { self.tap_window } else { Duration::from_ticks(0) };
+        let mut taps: u8 = 1;
+        while tap_window > Duration::from_ticks(0) {
+            Timer::after(DEBOUNCE_DELAY).await;
+            match select(self.wait_for_button_down(), Timer::after(tap_window)).await {
+                Either::First(_) => {
+                    Timer::after(DEBOUNCE_DELAY).await;
+                    self.wait_for_button_up().await;
+                    taps = taps.saturating_add(1);
+                }
+                Either::Second(()) => break
+            }
+        }
+
+        let press_duration = PressDuration::from_taps(taps);
+        debug_println!("{:?}!", press_duration);
         press_duration
     }
 
     #[inline]
     pub async fn wait_for_press(&mut self) -> &mut Self {
-        self.0.wait_for_rising_edge().await;
+        let _ = self.input.wait_for_rising_edge().await;
         self
     }
 }
 
+// Percent of LONG_PRESS that a hold has lasted so far
+pub fn hold_progress(held: Duration) -> u8 {
+    (held.as_millis() * 100 / LONG_PRESS.as_millis()).min(100) as u8
+}
+
+// Feedback while the button is held down
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HoldEvent {
+    // Held past HOLD_START, might become a long press
+    Started,
+    // Progress towards a long press, 0-100
+    Repeat(u8),
+    // Held for LONG_PRESS, a long press was registered
+    Completed,
+    // Released before LONG_PRESS
+    Cancelled,
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum PressDuration {
     Short,
-    Long
+    Long,
+    // Two short presses within the tap window
+    DoubleClick,
+    // Three or more short presses, each within the tap window of the last
+    MultiTap(u8)
+}
+
+impl PressDuration {
+    pub const fn from_taps(taps: u8) -> Self {
+        match taps {
+            0 | 1 => Self::Short,
+            2 => Self::DoubleClick,
+            n => Self::MultiTap(n)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use embassy_futures::{block_on, join::join};
+
+    use super::*;
+    use crate::mock_pin::MockPin;
+
+    async fn tap(pin: &MockPin, held: u64) {
+        pin.set(true);
+        Timer::after_millis(held).await;
+        pin.set(false);
+    }
+
+    fn press(script: impl core::future::Future<Output = ()>, pin: MockPin) -> (PressDuration, Vec<HoldEvent>) {
+        let mut button = Button::new(pin);
+        let mut holds = Vec::new();
+        let (press, ()) = block_on(join(button.press_with_hold(|hold| holds.push(hold), || true), script));
+        (press, holds)
+    }
+
+    #[test]
+    fn short_press() {
+        let pin = MockPin::default();
+        let (duration, holds) = press(tap(&pin, 100), pin.clone());
+        assert_eq!(duration, PressDuration::Short);
+        assert!(holds.is_empty());
+    }
+
+    #[test]
+    fn double_click() {
+        let pin = MockPin::default();
+        let script = async {
+            tap(&pin, 100).await;
+            Timer::after_millis(150).await;
+            tap(&pin, 100).await;
+        };
+        let (duration, _) = press(script, pin.clone());
+        assert_eq!(duration, PressDuration::DoubleClick);
+    }
+
+    #[test]
+    fn taps_outside_the_window_are_separate_presses() {
+        let pin = MockPin::default();
+        let script = async {
+            tap(&pin, 100).await;
+            Timer::after(DEFAULT_TAP_WINDOW + Duration::from_millis(200)).await;
+            tap(&pin, 100).await;
+        };
+        let (duration, _) = press(script, pin.clone());
+        assert_eq!(duration, PressDuration::Short);
+    }
+
+    #[test]
+    fn hold_reports_progress_then_long_press() {
+        let pin = MockPin::default();
+        let (duration, holds) = press(tap(&pin, 1300), pin.clone());
+        assert_eq!(duration, PressDuration::Long);
+
+        assert_eq!(holds.first(), Some(&HoldEvent::Started));
+        assert_eq!(holds.last(), Some(&HoldEvent::Completed));
+        let progress: Vec<u8> = holds.iter().filter_map(|hold| match hold {
+            HoldEvent::Repeat(progress) => Some(*progress),
+            _ => None
+        }).collect();
+        assert!(!progress.is_empty());
+        assert!(progress.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn released_hold_is_cancelled() {
+        let pin = MockPin::default();
+        let (duration, holds) = press(tap(&pin, 500), pin.clone());
+        assert_eq!(duration, PressDuration::Short);
+        assert_eq!(holds.first(), Some(&HoldEvent::Started));
+        assert_eq!(holds.last(), Some(&HoldEvent::Cancelled));
+    }
+
+    #[test]
+    fn without_multi_tap_a_press_ends_on_release() {
+        let pin = MockPin::default();
+        let mut button = Button::new(pin.clone());
+        let script = async {
+            tap(&pin, 100).await;
+            Instant::now()
+        };
+        let ((duration, resolved), released) = block_on(join(
+            async { (button.press_with_hold(|_| {}, || false).await, Instant::now()) },
+            script
+        ));
+        assert_eq!(duration, PressDuration::Short);
+        assert!(resolved - released < DEFAULT_TAP_WINDOW);
+    }
 }
@@ -4,11 +4,17 @@ use crate::scenes_util::{UIAction, UINode, SceneData};
 #[derive(Debug, Clone, Copy)]
 pub struct ClickableElement {
     pub position: Rectangle,
-    pub value: u8,
     next_element: u8,
     prev_element: u8
 }
 
+impl ClickableElement {
+    // next/prev are indices into the scene's elements, where focus goes on MoveNext/MoveBack
+    pub const fn new(position: Rectangle, next_element: u8, prev_element: u8) -> Self {
+        ClickableElement { position, next_element, prev_element }
+    }
+}
+
 impl UINode for ClickableElement {
     fn get_position(&self) -> &Rectangle {
         &self.position
@@ -16,18 +22,17 @@ impl UINode for ClickableElement {
 
     fn handle_action(&mut self, scene: &mut SceneData, action: UIAction) {
        match action {
-            UIAction::MoveBack => {
+            UIAction::MoveBack(_) => {
                 scene.cursor_index = self.prev_element;
             }
-            UIAction::MoveNext => {
+            UIAction::MoveNext(_) => {
                 scene.cursor_index = self.next_element;
             }
-            UIAction::Select => {
-                self.value += 1;
-            }
             UIAction::Back => {
                 scene.cursor_index = self.prev_element;
             }
+            // The session acts on Select through the element's id
+            UIAction::Select | UIAction::Undo | UIAction::Home => {}
        }
     }
 }
@@ -1,14 +1,29 @@
+use core::sync::atomic::Ordering;
 use embassy_executor::{SpawnError, Spawner};
 use embassy_futures::select::{Either, select};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_time::{Duration, Ticker, Timer};
 use embassy_sync::channel::Channel;
-use crate::button::{Button, PressDuration};
-use crate::display_driver::DisplayDriver;
-use crate::payloads::{ Packet, SessionState };
-use crate::render_display::{ TFTNotifier, TFTRender };
-use crate::tft::{HardwareTFT, TFT};
-use crate::time_util::Time;
+use embassy_sync::signal::Signal;
+use crate::input::{ActionInput, MultiTapFlag};
+use crate::clock_source::ClockSource;
+use crate::history::{IntervalTracker, SessionHistory};
+use crate::payloads::{ OverlayEvent, Packet, Payload, SessionState, TimeUpdate };
+use crate::stats::StatsSummary;
+use crate::render_display::{ HoldNotifier, OverlayNotifier, TFTNotifier, TFTRender };
+use crate::constants::{MAIN_MENU, SETTINGS_MENU};
+use crate::config::{self, CONFIG_BACK, CONFIG_START};
+use crate::scenes::{CONFIG_TARO_PLUS_SCENE, CONFIG_TARO_SCENE, SETTINGS_SCENE};
+use crate::list_menu::{ListMenu, MenuAction, Setting};
+use crate::overlay::{Dialog, Toast};
+use crate::scenes_util::{Scene, SceneData, SceneManager, UIAction};
+use crate::time_util::{Time, TimerAdjust, TimerMode, TimerResolution, ADJUST_STEP};
+
+#[cfg(not(feature = "simulator"))]
+use crate::tft::HardwareTFT;
+
+// Asked before a multi-tap throws the running session away
+const END_SESSION_DIALOG: Dialog = Dialog::new("End session?", "The timer starts over next time");
 
 /*
  * Represents a single Ticker that increments 'run_duration' every tenth of a second
@@ -40,173 +55,537 @@ impl SingleClock {
 }
 
 impl SessionState {
-    pub async fn execute(
+    pub async fn execute<I: ActionInput>(
         self, 
         session: &mut DoubleTimerSession<'_>, 
-        button: &mut Button<'_>) -> Self 
+        input: &mut I) -> Self 
     {
         match self {
-            SessionState::MainMenu => self.execute_main_menu(session, button).await,
-            SessionState::Working => self.execute_working(session, button).await,
-            SessionState::Break => self.execute_break(session, button).await,
-            SessionState::Paused => self.execute_paused(session, button).await,
+            SessionState::MainMenu => self.execute_main_menu(session, input).await,
+            SessionState::Working => self.execute_working(session, input).await,
+            SessionState::Break => self.execute_break(session, input).await,
+            SessionState::Paused => self.execute_paused(session, input).await,
+            SessionState::Statistics => self.execute_statistics(session, input).await,
+            SessionState::Settings => self.execute_menu(SETTINGS_MENU, session, input).await,
+            SessionState::ConfigTaro => self.execute_config(CONFIG_TARO_SCENE, session, input).await,
+            SessionState::ConfigTaroPlus => self.execute_config(CONFIG_TARO_PLUS_SCENE, session, input).await,
         }
     }
 
-    async fn execute_main_menu(self, session: &mut DoubleTimerSession<'_>, button: &mut Button<'_>) -> Self {
+    async fn execute_main_menu<I: ActionInput>(self, session: &mut DoubleTimerSession<'_>, input: &mut I) -> Self {
+        self.execute_menu(MAIN_MENU, session, input).await
+    }
+
+    /*
+     * Any list menu screen. The renderer gets every action for its copy of the menu,
+     * this copy tells us what Select picked.
+     */
+    async fn execute_menu<I: ActionInput>(
+        self,
+        mut menu: ListMenu,
+        session: &mut DoubleTimerSession<'_>,
+        input: &mut I) -> Self
+    {
         session.set_state(self).await;
 
-        // Wait for any button press to start
-        match button.press_duration().await {
-            PressDuration::Short => {
-                esp_println::println!("menu -> working (short)");
-                Self::Working
+        loop {
+            let action = input.next_action().await;
+            // Submenus go back with a long press, the main menu has nowhere to go back to
+            let submenu = self != Self::MainMenu;
+            if submenu && matches!(action, UIAction::Back | UIAction::Home) {
+                debug_println!("menu -> main menu");
+                return Self::MainMenu;
             }
-            PressDuration::Long => {
-                esp_println::println!("menu -> break (long)");
-                Self::Break
+
+            session.scene_action(action).await;
+            match menu.step(action) {
+                Some(MenuAction::StartMode(mode)) => {
+                    debug_println!("menu -> working ({:?})", mode);
+                    session.set_mode(mode).await;
+                    return Self::Working;
+                }
+                Some(MenuAction::OpenScene(scene)) => {
+                    if let Some(next_state) = Self::showing(scene) {
+                        return next_state;
+                    }
+                }
+                Some(MenuAction::ToggleSetting(setting)) => session.toggle_setting(setting).await,
+                None => {}
             }
         }
     }
 
-    async fn execute_working(self, session: &mut DoubleTimerSession<'_>, button: &mut Button<'_>) -> Self {
+    // States with a timer on screen
+    const fn in_session(self) -> bool {
+        matches!(self, Self::Working | Self::Break | Self::Paused)
+    }
+
+    // Double click and multi-tap only mean something to the timers, elsewhere a press shouldn't wait for them
+    const fn binds_multi_tap(self) -> bool {
+        self.in_session()
+    }
+
+    // State that puts this scene on screen
+    fn showing(scene: Scene) -> Option<Self> {
+        match scene {
+            Scene::MainMenu => Some(Self::MainMenu),
+            Scene::Statistics => Some(Self::Statistics),
+            Scene::Settings => Some(Self::Settings),
+            Scene::ConfigTaro => Some(Self::ConfigTaro),
+            Scene::ConfigTaroPlus => Some(Self::ConfigTaroPlus),
+            Scene::Session => None,
+        }
+    }
+
+    /*
+     * Block lengths before a Taro session. The renderer gets every action for its copy
+     * of the scene, this copy holds the lengths once Start is picked.
+     */
+    async fn execute_config<I: ActionInput>(
+        self,
+        scene: SceneData,
+        session: &mut DoubleTimerSession<'_>,
+        input: &mut I) -> Self
+    {
         session.set_state(self).await;
-        match button.press_duration().await {
-            PressDuration::Short => {
-                esp_println::println!("working -> break (short)");
-                Self::Break
+        let mut form = SceneManager::default();
+        let _ = form.initialize_scene(scene);
+
+        loop {
+            let action = input.next_action().await;
+            let focused = form.current_scene.elements
+                .get(form.current_scene.cursor_index as usize)
+                .and_then(|element| element.id);
+
+            session.scene_action(action).await;
+            form.handle_action(action);
+            if action != UIAction::Select {
+                continue;
             }
-            PressDuration::Long => {
-                esp_println::println!("working -> paused (long)");
-                Self::Paused
+
+            match focused {
+                Some(CONFIG_START) => {
+                    if let Some(mode) = config::timer_mode(scene.scene, &form.current_scene.elements) {
+                        debug_println!("config -> working ({:?})", mode);
+                        session.set_mode(mode).await;
+                        return Self::Working;
+                    }
+                }
+                Some(CONFIG_BACK) => {
+                    debug_println!("config -> main menu");
+                    return Self::MainMenu;
+                }
+                _ => {}
             }
         }
     }
 
-    async fn execute_break(self, session: &mut DoubleTimerSession<'_>, button: &mut Button<'_>) -> Self {
+    async fn execute_statistics<I: ActionInput>(self, session: &mut DoubleTimerSession<'_>, input: &mut I) -> Self {
+        session.set_state(self).await;
+
+        // Read-only screen, any press goes back
+        while let UIAction::MoveNext(_) | UIAction::MoveBack(_) = input.next_action().await {}
+        debug_println!("statistics -> menu");
+        Self::MainMenu
+    }
+
+    async fn execute_working<I: ActionInput>(self, session: &mut DoubleTimerSession<'_>, input: &mut I) -> Self {
         session.set_state(self).await;
-        match button.press_duration().await {
-            PressDuration::Short => {
-                esp_println::println!("break -> working (short)");
-                Self::Working
+        loop {
+            match select(input.next_action(), session.block_expired()).await {
+                Either::First(UIAction::Select) => {
+                    debug_println!("working -> break (select)");
+                    return Self::Break;
+                }
+                Either::First(UIAction::Back) => {
+                    debug_println!("working -> paused (back)");
+                    return Self::Paused;
+                }
+                Either::First(action) => {
+                    if let Some(next_state) = Self::execute_timer_action(action, session, input).await {
+                        return next_state;
+                    }
+                }
+                Either::Second(next_state) => {
+                    debug_println!("working -> break (block finished)");
+                    return next_state;
+                }
             }
-            PressDuration::Long => {
-                esp_println::println!("working -> paused (long)");
-                Self::Paused
+        }
+    }
+
+    async fn execute_break<I: ActionInput>(self, session: &mut DoubleTimerSession<'_>, input: &mut I) -> Self {
+        session.set_state(self).await;
+        loop {
+            match select(input.next_action(), session.block_expired()).await {
+                Either::First(UIAction::Select) => {
+                    debug_println!("break -> working (select)");
+                    return Self::Working;
+                }
+                Either::First(UIAction::Back) => {
+                    debug_println!("break -> paused (back)");
+                    return Self::Paused;
+                }
+                Either::First(action) => {
+                    if let Some(next_state) = Self::execute_timer_action(action, session, input).await {
+                        return next_state;
+                    }
+                }
+                Either::Second(next_state) => {
+                    debug_println!("break -> working (block finished)");
+                    return next_state;
+                }
             }
         }
     }
 
-    async fn execute_paused(self, session: &mut DoubleTimerSession<'_>, button: &mut Button<'_>) -> Self {
+    async fn execute_paused<I: ActionInput>(self, session: &mut DoubleTimerSession<'_>, input: &mut I) -> Self {
         session.set_state(self).await;
-        match button.press_duration().await {
-            PressDuration::Short => {
-                esp_println::println!("pause -> working (short)");
-                Self::Working
+        loop {
+            match input.next_action().await {
+                UIAction::Select => {
+                    debug_println!("pause -> working (select)");
+                    return Self::Working;
+                }
+                UIAction::Back => {
+                    debug_println!("pause -> break (back)");
+                    return Self::Break;
+                }
+                action => {
+                    if let Some(next_state) = Self::execute_timer_action(action, session, input).await {
+                        return next_state;
+                    }
+                }
             }
-            PressDuration::Long => {
-                esp_println::println!("pause -> break (long)");
-                Self::Break
+        }
+    }
+
+    // Actions that do the same in every timer state.
+    // Rotation corrects the timer, double click undoes the last switch, multi-tap ends the session.
+    // Returns the state to switch to, None to stay.
+    async fn execute_timer_action<I: ActionInput>(
+        action: UIAction,
+        session: &mut DoubleTimerSession<'_>,
+        input: &mut I) -> Option<Self>
+    {
+        match action {
+            // A minute per detent, however fast the knob is turned
+            UIAction::MoveNext(turn) => {
+                session.adjust_timer(TimerAdjust::Forward(ADJUST_STEP * turn.detents as u32)).await;
+                None
+            }
+            UIAction::MoveBack(turn) => {
+                session.adjust_timer(TimerAdjust::Back(ADJUST_STEP * turn.detents as u32)).await;
+                None
+            }
+            UIAction::Undo => {
+                let restored = session.undo_switch().await;
+                debug_println!("undo -> {:?} (double click)", restored);
+                Some(restored)
+            }
+            UIAction::Home => {
+                if !session.confirm(END_SESSION_DIALOG, input).await {
+                    return None;
+                }
+                debug_println!("session -> menu (multi-tap)");
+                Some(Self::MainMenu)
             }
+            UIAction::Select | UIAction::Back => None
         }
     }
 
-    pub(crate) fn render(self, time: &mut Time) -> Option<(Packet, Duration)> {
+}
+
+impl SessionState {
+    pub(crate) fn render<C: ClockSource>(self, time: &mut Time<C>) -> Option<(Packet, Duration)> {
         match self {
             Self::MainMenu => None, // doesn't send time packets
+            Self::Statistics => None,
+            Self::Settings => None,
+            Self::ConfigTaro | Self::ConfigTaroPlus => None,
             Self::Working => Some(Self::render_working(time)),
             Self::Break => Some(Self::render_break(time)),
             Self::Paused => Some(Self::render_paused(time))
         }
     }
 
-    fn render_working(time: &mut Time) -> (Packet, Duration) {
+    fn render_working<C: ClockSource>(time: &mut Time<C>) -> (Packet, Duration) {
         let (display_time, sleep_dur) = time.sleep_for_work();
-        let panel = Packet::from_time(display_time, SessionState::Working);
+        let panel = Packet::from_time(TimeUpdate {
+            digits: display_time,
+            state: SessionState::Working,
+            cycle: time.cycle(),
+            adjusted_mins: time.adjusted_mins(SessionState::Working),
+            progress: time.block_progress(SessionState::Working)
+        });
         (panel, sleep_dur)
     }
 
-    fn render_break(time: &mut Time) -> (Packet, Duration) {
+    fn render_break<C: ClockSource>(time: &mut Time<C>) -> (Packet, Duration) {
         let (display_time, sleep_dur) = time.sleep_for_break();
-        let panel = Packet::from_time(display_time, SessionState::Break);
+        let panel = Packet::from_time(TimeUpdate {
+            digits: display_time,
+            state: SessionState::Break,
+            cycle: time.cycle(),
+            adjusted_mins: time.adjusted_mins(SessionState::Break),
+            progress: time.block_progress(SessionState::Break)
+        });
         (panel, sleep_dur)
     }
 
-    fn render_paused(time: &mut Time) -> (Packet, Duration) {
+    fn render_paused<C: ClockSource>(time: &mut Time<C>) -> (Packet, Duration) {
         let (display_time, sleep_dur) = time.sleep_for_pause();
-        let panel = Packet::from_time(display_time, SessionState::Paused);
+        // The cycle counter belongs to the work/break timers, it would overlap the break digits here
+        let panel = Packet::from_time(TimeUpdate {
+            digits: display_time,
+            state: SessionState::Paused,
+            cycle: None,
+            adjusted_mins: 0,
+            progress: time.block_progress(SessionState::Paused)
+        });
         (panel, sleep_dur)
     }
 
+    // State to switch to once the current countdown block has run out
+    pub(crate) fn next_on_expiry<C: ClockSource>(self, time: &Time<C>) -> Option<Self> {
+        if !time.block_expired(self) {
+            return None;
+        }
+        match self {
+            Self::Working => Some(Self::Break),
+            Self::Break => Some(Self::Working),
+            _ => None
+        }
+    }
+
 }
 
+#[derive(Clone, Copy)]
 pub enum SessionNotice {
     SetState(SessionState),
-    AdjustTimer(Duration)
+    SetMode(TimerMode),
+    SetResolution(TimerResolution),
+    AdjustTimer(TimerAdjust),
+    UndoSwitch,
+    // Passed straight on to the renderer, doesn't touch the timer
+    SceneAction(UIAction),
+    ToggleSetting(Setting)
 }
 
 impl SessionNotice {
-    pub(crate) fn apply(self, time: &mut Time, state: &mut SessionState) {
+    pub(crate) fn apply<C: ClockSource>(self, time: &mut Time<C>, state: &mut SessionState) {
         match self {
-            Self::AdjustTimer(delta) => {
-                *time += delta
+            Self::AdjustTimer(adjust) => {
+                time.adjust(*state, adjust)
             }
             Self::SetState(new_state) => {
                 *state = new_state
             }
+            Self::SetMode(mode) => {
+                time.set_mode(mode)
+            }
+            Self::SetResolution(resolution) => {
+                time.set_resolution(resolution)
+            }
+            Self::UndoSwitch => {
+                if let Some(previous) = time.undo_switch() {
+                    *state = previous
+                }
+            }
+            Self::SceneAction(_) => {}
+            Self::ToggleSetting(Setting::Resolution) => {
+                time.set_resolution(time.resolution().next())
+            }
         }
     }
 }
 
-pub type SessionNotifier = (SessionOuterNotifier, TFTNotifier);
+pub type SessionNotifier = (SessionOuterNotifier, TFTNotifier, SessionExpiryNotifier, HoldNotifier, OverlayNotifier, MultiTapFlag);
 pub type SessionOuterNotifier = Channel<CriticalSectionRawMutex, SessionNotice, 4>;
+// Signalled by device_loop when it switched state on its own (block ran out, undo)
+pub type SessionExpiryNotifier = Signal<CriticalSectionRawMutex, SessionState>;
 
-pub struct DoubleTimerSession<'spi>(&'spi SessionOuterNotifier);
+pub struct DoubleTimerSession<'spi>(&'spi SessionOuterNotifier, &'spi SessionExpiryNotifier, &'spi OverlayNotifier, &'spi MultiTapFlag);
 
 impl<'spi> DoubleTimerSession<'spi> {
+    #[cfg(not(feature = "simulator"))]
     pub fn new(
         tft: HardwareTFT,
         spawner: Spawner,
         notifier: &'static SessionNotifier,
+        history: &'static SessionHistory,
     ) -> Result<Self, SpawnError> {
-        let (outer_notifier, tft_notifier) = notifier;
-        let _tft = TFTRender::new(tft, tft_notifier, spawner)?;
-        spawner.spawn(device_loop(outer_notifier, tft_notifier))?;
-        Ok(Self(outer_notifier))
+        let (_, tft_notifier, _, hold_notifier, overlay_notifier, _) = notifier;
+        let _tft = TFTRender::new(tft, tft_notifier, hold_notifier, overlay_notifier, spawner)?;
+        Self::without_renderer(spawner, notifier, history)
+    }
+
+    // Only spawns device_loop; the caller is responsible for draining the TFT notifier.
+    // Used by the simulator, which has to own its window on the main thread.
+    pub fn without_renderer(
+        spawner: Spawner,
+        notifier: &'static SessionNotifier,
+        history: &'static SessionHistory,
+    ) -> Result<Self, SpawnError> {
+        let (outer_notifier, tft_notifier, expiry_notifier, _, overlay_notifier, multi_tap) = notifier;
+        spawner.spawn(device_loop(outer_notifier, tft_notifier, expiry_notifier, overlay_notifier, history))?;
+        Ok(Self(outer_notifier, expiry_notifier, overlay_notifier, multi_tap))
     }
 
     pub(crate) async fn set_state(&self, new_state: SessionState) {
+        // Any pending expiry belongs to the state we're leaving
+        self.1.reset();
+        self.3.store(new_state.binds_multi_tap(), Ordering::Relaxed);
         self.0.send(SessionNotice::SetState(new_state)).await;
     }
 
+    // Countdown mode (and block lengths) used by the next session
+    pub async fn set_mode(&self, mode: TimerMode) {
+        self.0.send(SessionNotice::SetMode(mode)).await;
+    }
+
+    // Show tenths/hundredths; device_loop ticks faster to match
+    pub async fn set_resolution(&self, resolution: TimerResolution) {
+        self.0.send(SessionNotice::SetResolution(resolution)).await;
+    }
+
+    // Go back to the state before the last switch, resolves with the state device_loop ended up in
+    pub(crate) async fn undo_switch(&self) -> SessionState {
+        self.1.reset();
+        self.0.send(SessionNotice::UndoSwitch).await;
+        self.1.wait().await
+    }
+
+    pub async fn toggle_setting(&self, setting: Setting) {
+        self.0.send(SessionNotice::ToggleSetting(setting)).await;
+    }
+
+    // Hand an action to the focused element of the scene on screen
+    pub async fn scene_action(&self, action: UIAction) {
+        self.0.send(SessionNotice::SceneAction(action)).await;
+    }
+
+    /*
+     * Puts the dialog over the current scene and waits for an answer.
+     * The renderer gets every action for its copy of the dialog, like with a ListMenu.
+     */
+    pub(crate) async fn confirm<I: ActionInput>(&self, mut dialog: Dialog, input: &mut I) -> bool {
+        self.2.send(OverlayEvent::Dialog(dialog)).await;
+        let answer = loop {
+            let action = input.next_action().await;
+            if let Some(answer) = dialog.step(action) {
+                break answer;
+            }
+            self.2.send(OverlayEvent::Action(action)).await;
+        };
+        self.2.send(OverlayEvent::CloseDialog).await;
+        answer
+    }
+
+    // Nudge the running work/break timer, ignored outside of a session
+    pub async fn adjust_timer(&self, adjust: TimerAdjust) {
+        self.0.send(SessionNotice::AdjustTimer(adjust)).await;
+    }
+
+    // Resolves with the new state once device_loop switches blocks on its own
+    pub(crate) async fn block_expired(&self) -> SessionState {
+        self.1.wait().await
+    }
+
     #[must_use]
     pub const fn notifier() -> SessionNotifier {
-        (Channel::new(), TFTRender::notifier())
+        (Channel::new(), TFTRender::notifier(), Signal::new(), Signal::new(), Channel::new(), MultiTapFlag::new(false))
     }
 
 }
 
 #[embassy_executor::task]
-async fn device_loop(session_notifier: &'static SessionOuterNotifier, tft_notifier: &'static TFTNotifier) -> ! {
-    let mut time = Time::default();
+async fn device_loop(
+    session_notifier: &'static SessionOuterNotifier,
+    tft_notifier: &'static TFTNotifier,
+    expiry_notifier: &'static SessionExpiryNotifier,
+    overlay_notifier: &'static OverlayNotifier,
+    history: &'static SessionHistory,
+) -> ! {
+    let mut time: Time = Time::default();
     let mut session_state = SessionState::default();
+    let mut interval = IntervalTracker::default();
+    let mut shown_state = session_state;
 
     loop {
+        // Screens without a running timer are drawn once when entered
+        if session_state != shown_state {
+            if (shown_state, session_state) == (SessionState::Working, SessionState::Break) {
+                overlay_notifier.send(OverlayEvent::Toast(Toast::new("Break started"))).await;
+            }
+            shown_state = session_state;
+            match session_state {
+                SessionState::MainMenu => tft_notifier.signal(Packet::menu()),
+                SessionState::Settings => tft_notifier.signal(Packet(Payload::NewScene(&SETTINGS_SCENE))),
+                SessionState::ConfigTaro => tft_notifier.signal(Packet(Payload::NewScene(&CONFIG_TARO_SCENE))),
+                SessionState::ConfigTaroPlus => tft_notifier.signal(Packet(Payload::NewScene(&CONFIG_TARO_PLUS_SCENE))),
+                SessionState::Statistics => {
+                    let mut log = history.lock().await;
+                    let now = log.timestamp(time.clock().now());
+                    let summary = StatsSummary::from_records(log.records(), now).with_calendar_days(log.has_clock());
+                    tft_notifier.signal(Packet(Payload::Stats(summary)));
+                }
+                _ => {}
+            }
+        }
+
+        // Persist the work/break interval that just ended, if the state changed
+        if let Some((kind, start, end)) = interval.transition(session_state, time.clock().now()) {
+            let saved = history.lock().await.append_interval(kind, start, end);
+            match saved {
+                // The session was ended, let the user know it wasn't lost
+                Ok(()) if session_state == SessionState::MainMenu => {
+                    overlay_notifier.send(OverlayEvent::Toast(Toast::new("Saved"))).await;
+                }
+                Ok(()) => {}
+                Err(err) => debug_println!("history: failed to save interval: {:?}", err),
+            }
+        }
+
         if let Some((panel, sleep_dur)) = session_state.render(&mut time) {
             tft_notifier.signal(panel);
 
+            // Countdown block ran out: move on without waiting for a button press
+            if let Some(next_state) = session_state.next_on_expiry(&time) {
+                session_state = next_state;
+                expiry_notifier.signal(next_state);
+                continue;
+            }
+
             if let Either::First(notification) = 
                 select(session_notifier.receive(), Timer::after(sleep_dur)).await
             {
+                // Keep the saved interval in line with the corrected timer
+                if let SessionNotice::AdjustTimer(adjust) = notification {
+                    interval.adjust(session_state, adjust, time.clock().now());
+                }
                 notification.apply(&mut time, &mut session_state);
+
+                if let SessionNotice::UndoSwitch = notification {
+                    interval.undo(session_state);
+                    // The main task waits for the state the undo left us in
+                    expiry_notifier.signal(session_state);
+                }
             }
         } else {
             // Menu state: just wait for state change notification
             let notification = session_notifier.receive().await;
+            if let SessionNotice::SceneAction(action) = notification {
+                // Queued, a fast turn mustn't lose steps to the next one
+                overlay_notifier.send(OverlayEvent::Action(action)).await;
+                continue;
+            }
             notification.apply(&mut time, &mut session_state);
 
-            // Reset time when going from menu to a session
-            time = Time::default();
+            // Reset time when going from menu to a session, keeping the selected mode
+            if session_state.in_session() {
+                time.restart();
+            }
         }
     }
 }
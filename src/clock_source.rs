@@ -0,0 +1,66 @@
+use core::cell::Cell;
+use embassy_time::{Duration, Instant};
+
+/*
+ * Where 'Time' reads the current instant from.
+ * Lets the session timers run against embassy on the ESP32
+ * and against a hand-driven clock on the host.
+ */
+pub trait ClockSource {
+    fn now(&self) -> Instant;
+}
+
+impl<C: ClockSource> ClockSource for &C {
+    #[inline]
+    fn now(&self) -> Instant {
+        (*self).now()
+    }
+}
+
+// Reads the embassy time driver (ESP32 timer group, or the std driver in the simulator)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EmbassyClock;
+
+impl ClockSource for EmbassyClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/*
+ * Clock that only moves when told to.
+ * Share it by reference ('&FakeClock' is also a ClockSource)
+ * so it can be advanced while a 'Time' is reading from it.
+ */
+#[derive(Debug)]
+pub struct FakeClock {
+    now: Cell<Instant>,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeClock {
+    pub const fn new() -> Self {
+        FakeClock { now: Cell::new(Instant::from_ticks(0)) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+
+    pub fn set(&self, now: Instant) {
+        self.now.set(now);
+    }
+}
+
+impl ClockSource for FakeClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
@@ -1,4 +1,4 @@
-use embedded_graphics::{pixelcolor::{raw::RawU16, *}, prelude::{Point, RawData}};
+use embedded_graphics::{pixelcolor::{raw::RawU16, *}, prelude::{Point, RawData}, primitives::Rectangle};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RGBa {
@@ -7,7 +7,7 @@ pub struct RGBa {
 }
 
 impl RGBa {
-    pub const fn new(color: C, alpha: u8) -> Self {
+    pub const fn new(color: Rgb565, alpha: u8) -> Self {
         Self {color, alpha}
     }
 
@@ -20,7 +20,7 @@ impl RGBa {
     }
 
     #[inline(always)]
-    fn blend(&self, background: Rgb565) -> Rgb565 {
+    pub(crate) fn blend(&self, background: Rgb565) -> Rgb565 {
         if self.alpha == 255 {
             return self.color;
         }
@@ -34,29 +34,23 @@ impl RGBa {
 
 #[inline(always)]
 fn blend_rgb565(source: Rgb565, destination: Rgb565, alpha: u8) -> Rgb565 {
-    let a = alpha as u32;
-    let src = RawU16::from(source).into_inner() as u32;
-    let dest = RawU16::from(destination).into_inner() as u32;
-
-    // Separate R+B (packed) and G channels
-    let src_rb = src & 0xF81F;
-    let dest_rb = dest & 0xF81F;
-    let src_g = src & 0x07E0;
-    let dest_g = dest & 0x07E0;
-
-    // Blend R+B together (they won't interfere in a u32 space)
-    // dest + ((src - dest)) * alpha) >> 8
-    let rb = dest_rb + (((src_rb.wrapping_sub(dest_rb)).wrapping_mul(a)) >> 8);
-    // Mask to valid R+B bits
-    let rb = rb & 0xF81F;  
-
-    // Now doing G channel on it's own
-    // ain't nothing but a G thing
-    let g = dest_g + (((src_g.wrapping_sub(dest_g)).wrapping_mul(a)) >> 8);
-    let g = g & 0x07E0;  
-
-    // Recombine
-    let raw = (rb | g) as u16;
+    // 0-32, five bits of alpha is all 5-bit channels can show anyway
+    let a = (alpha as u32 + 4) >> 3;
+
+    // Spread the channels out with G in the upper half, so each has room to be multiplied
+    // without running into the next: ----- GGGGGG ----- RRRRR ------ BBBBB
+    let spread = |color: Rgb565| {
+        let raw = RawU16::from(color).into_inner() as u32;
+        (raw | (raw << 16)) & 0x07E0_F81F
+    };
+    let src = spread(source);
+    let dest = spread(destination);
+
+    // All three channels at once: (src * a + dest * (32 - a)) / 32
+    let mixed = ((src * a + dest * (32 - a)) >> 5) & 0x07E0_F81F;
+
+    // Fold G back down between R and B
+    let raw = (mixed | (mixed >> 16)) as u16;
     Rgb565::from(RawU16::new(raw))
 }
 
@@ -65,7 +59,7 @@ fn blend_rgb565(source: Rgb565, destination: Rgb565, alpha: u8) -> Rgb565 {
 // ------------------------------------------------------------
 
 use crate::buffer_backend::BufferData;
-use crate::constants::{DISPLAY_WIDTH, DISPLAY_HEIGHT, PIXEL_COUNT};
+use crate::constants::{DISPLAY_WIDTH, DISPLAY_HEIGHT};
 
 impl BufferData {
     // Rectangular (only) blending directly over a region in the framebuffer
@@ -104,11 +98,9 @@ impl BufferData {
         let x0 = position.x.max(0) as u32;
         let y0 = position.y.max(0) as u32;
 
-        let mut px = 0u32;
-        for (color, alpha) in pixels {
+        for (px, (color, alpha)) in (0u32..).zip(pixels) {
             let local_x = px % width;
             let local_y = px / width;
-            px += 1;
 
             if local_y >= height {
                 break;
@@ -1,5 +1,4 @@
 use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
-use heapless::Vec;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GradientDirection {
@@ -170,105 +169,13 @@ fn pow_approx(base: f32, exp: f32) -> f32 {
 fn ln_approx(x: f32) -> f32 {
     // ln(x) = (as_int / 2^23 - 127) * ln(2)
     let bits = x.to_bits() as f32;
-    let log2_approx = bits * 1.1920929e-7 - 126.94269504;
+    let log2_approx = bits * 1.1920929e-7 - 126.942_695;
     log2_approx * core::f32::consts::LN_2
 }
 
 fn exp2_approx(x: f32) -> f32 {
     // 2^x by bit manipulation
-    let clamped = x.max(-126.0).min(126.0);
-    let bits = ((clamped + 126.94269504) * 8388608.0) as u32;
+    let clamped = x.clamp(-126.0, 126.0);
+    let bits = ((clamped + 126.942_695) * 8388608.0) as u32;
     f32::from_bits(bits)
 }
-
-fn split_rgb(color: Rgb565) -> (f32, f32, f32) {
-    let red = color.r() as f32;
-    let green = color.g() as f32;
-    let blue = color.b() as f32;
-    (red, green, blue)
-}
-
-fn normalize(color: Rgb565) -> (f32, f32, f32) {
-    let (red, green, blue) = split_rgb(color);
-
-    let normalized_red = red as f32 / 255.0;
-    let normalized_green = green as f32 / 255.0;
-    let normalized_blue = blue as f32 / 255.0;
-
-    (normalized_red, normalized_green, normalized_blue)
-}
-
-fn mix(color_1: Rgb565, color_2: Rgb565) -> (f32, f32, f32) {
-    let red_1 = color_1.r() as f32;
-    let red_2 = color_2.r() as f32;
-    let green_1 = color_1.r() as f32;
-    let green_2 = color_2.g() as f32;
-    let blue_1 = color_1.b() as f32;
-    let blue_2 = color_2.b() as f32;
-
-    let mix_red = (red_1 + red_2) / 2.0;
-    let mix_green = (green_1 + green_2) / 2.0;
-    let mix_blue = (blue_1 + blue_2) / 2.0;
-
-    (mix_red, mix_green, mix_blue)
-}
-
-fn inverse_srgb_companding(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
-    let mut r = micromath::F32::from(r);
-    let mut g = micromath::F32::from(g);
-    let mut b = micromath::F32::from(b);
-    if r > 0.04045 {
-        r = ((r + 0.055) / 1.055).powf(micromath::F32::from(2.4));
-    } else {
-        r /= micromath::F32::from(12.92)
-    }
-
-    if g > 0.04045 {
-        g = ((g + 0.055) / 1.055).powf(micromath::F32::from(2.4));
-    } else {
-        g /= micromath::F32::from(12.92)
-    }
-
-    if b > 0.04045 {
-        b = ((b + 0.055) / 1.055).powf(micromath::F32::from(2.4));
-    } else {
-        b /= micromath::F32::from(12.92)
-    }
-    (r.into(), g.into(), b.into())
-}
-
-fn srgb_companding(r: f32, g: f32, b: f32) -> Rgb565 {
-    // Normalize channels from 0-255 to 0-1
-    let normal_r = r / 255.0;
-    let normal_g = g / 255.0;
-    let normal_b = b / 255.0;
-    let mut normal_r = micromath::F32::from(normal_r);
-    let mut normal_g = micromath::F32::from(normal_g);
-    let mut normal_b = micromath::F32::from(normal_b);
-
-    // Apply companding to R, G, and B channels
-    if normal_r > 0.0031308 {
-        normal_r = ( 1.055 * normal_r.powf(micromath::F32::from(1.0 / 2.4)) ) - 0.055;
-    } else {
-        normal_r *= micromath::F32::from(12.92)
-    }
-
-    if normal_g > 0.0031308 {
-        normal_g = ( 1.055 * normal_g.powf(micromath::F32::from(1.0 / 2.4)) ) - 0.055;
-    } else {
-        normal_g *= micromath::F32::from(12.92)
-    }
-
-    if normal_b > 0.0031308 {
-        normal_b = ( 1.055 * normal_b.powf(micromath::F32::from(1.0 / 2.4)) ) - 0.055;
-    } else {
-        normal_b *= micromath::F32::from(12.92)
-    }
-
-    let result_r = ( normal_r * 255.0 ).round().0 as u8;
-    let result_g = ( normal_g * 255.0 ).round().0 as u8;
-    let result_b = ( normal_b * 255.0 ).round().0 as u8;
-    Rgb565::new(result_r, result_g, result_b)
-}
-
-
@@ -0,0 +1,148 @@
+use embassy_time::Duration;
+
+use crate::{
+    scenes_util::{ElementId, ElementList, Scene, UIType},
+    time_util::{TaroConfig, TaroPlusConfig, TimerMode},
+};
+
+// Element ids used by CONFIG_TARO_SCENE and CONFIG_TARO_PLUS_SCENE
+pub const CONFIG_WORK: ElementId = ElementId(1);
+pub const CONFIG_BREAK: ElementId = ElementId(2);
+pub const CONFIG_START: ElementId = ElementId(3);
+pub const CONFIG_BACK: ElementId = ElementId(4);
+pub const CONFIG_LONG_BREAK: ElementId = ElementId(5);
+pub const CONFIG_CYCLES: ElementId = ElementId(6);
+
+// A block of 00:00 would run out the moment it started
+const MIN_BLOCK: Duration = Duration::from_secs(60);
+
+fn block(elements: &ElementList, id: ElementId, default: Duration) -> Duration {
+    match elements.find(id).map(|element| element.ui) {
+        Some(UIType::Duration(editor)) => editor.duration().max(MIN_BLOCK),
+        _ => default,
+    }
+}
+
+fn cycles(elements: &ElementList, default: u8) -> u8 {
+    match elements.find(CONFIG_CYCLES).map(|element| element.ui) {
+        Some(UIType::Digits(digits)) => digits.current_digit.max(1),
+        _ => default,
+    }
+}
+
+// Mode picked on a config scene, from what its elements hold now
+pub fn timer_mode(scene: Scene, elements: &ElementList) -> Option<TimerMode> {
+    match scene {
+        Scene::ConfigTaro => {
+            let default = TaroConfig::DEFAULT;
+            Some(TimerMode::Taro(TaroConfig {
+                work_block: block(elements, CONFIG_WORK, default.work_block),
+                break_block: block(elements, CONFIG_BREAK, default.break_block),
+            }))
+        }
+        Scene::ConfigTaroPlus => {
+            let default = TaroPlusConfig::DEFAULT;
+            Some(TimerMode::TaroPlus(TaroPlusConfig {
+                work_block: block(elements, CONFIG_WORK, default.work_block),
+                break_block: block(elements, CONFIG_BREAK, default.break_block),
+                long_break_block: block(elements, CONFIG_LONG_BREAK, default.long_break_block),
+                cycles: cycles(elements, default.cycles),
+            }))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        scenes::{CONFIG_TARO_PLUS_SCENE, CONFIG_TARO_SCENE},
+        scenes_util::{SceneData, SceneManager, Turn, UIAction},
+    };
+
+    fn form(scene: SceneData) -> SceneManager {
+        let mut form = SceneManager::default();
+        form.initialize_scene(scene).unwrap();
+        form
+    }
+
+    #[test]
+    fn scenes_start_at_the_defaults() {
+        let taro = form(CONFIG_TARO_SCENE).current_scene;
+        assert_eq!(timer_mode(taro.scene, &taro.elements), Some(TimerMode::Taro(TaroConfig::DEFAULT)));
+
+        let plus = form(CONFIG_TARO_PLUS_SCENE).current_scene;
+        assert_eq!(timer_mode(plus.scene, &plus.elements), Some(TimerMode::TaroPlus(TaroPlusConfig::DEFAULT)));
+    }
+
+    fn select(form: &mut SceneManager, times: usize) {
+        for _ in 0..times {
+            form.handle_action(UIAction::Select);
+        }
+    }
+
+    #[test]
+    fn edits_end_up_in_the_taro_mode() {
+        let mut form = form(CONFIG_TARO_SCENE);
+        // Work: hours +1 -> 01:25
+        select(&mut form, 1);
+        form.handle_action(UIAction::MoveNext(Turn::steps(1)));
+        select(&mut form, 3);
+        // Break: minutes +3 -> 00:08
+        select(&mut form, 3);
+        form.handle_action(UIAction::MoveNext(Turn::steps(3)));
+        select(&mut form, 1);
+
+        let scene = &form.current_scene;
+        assert_eq!(scene.elements[scene.cursor_index as usize].id, Some(CONFIG_START));
+        let expected = TaroConfig {
+            work_block: Duration::from_secs(85 * 60),
+            break_block: Duration::from_secs(8 * 60),
+        };
+        assert_eq!(timer_mode(scene.scene, &scene.elements), Some(TimerMode::Taro(expected)));
+    }
+
+    #[test]
+    fn edits_end_up_in_the_taro_plus_mode() {
+        let mut form = form(CONFIG_TARO_PLUS_SCENE);
+        // Work: hours +1 -> 01:25
+        select(&mut form, 1);
+        form.handle_action(UIAction::MoveNext(Turn::steps(1)));
+        select(&mut form, 3);
+        // Break: minutes +3 -> 00:08
+        select(&mut form, 3);
+        form.handle_action(UIAction::MoveNext(Turn::steps(3)));
+        select(&mut form, 1);
+        // Long break: tens of minutes -1 -> 00:05
+        select(&mut form, 2);
+        form.handle_action(UIAction::MoveBack(Turn::steps(1)));
+        select(&mut form, 2);
+        // Cycles all the way down, a session still needs one
+        form.handle_action(UIAction::MoveBack(Turn::steps(9)));
+        select(&mut form, 1);
+
+        let scene = &form.current_scene;
+        assert_eq!(scene.elements[scene.cursor_index as usize].id, Some(CONFIG_START));
+        let expected = TaroPlusConfig {
+            work_block: Duration::from_secs(85 * 60),
+            break_block: Duration::from_secs(8 * 60),
+            long_break_block: Duration::from_secs(5 * 60),
+            cycles: 1,
+        };
+        assert_eq!(timer_mode(scene.scene, &scene.elements), Some(TimerMode::TaroPlus(expected)));
+    }
+
+    #[test]
+    fn empty_block_is_a_minute_long() {
+        let mut form = form(CONFIG_TARO_SCENE);
+        // Work 00:25 down by ten hours
+        form.handle_action(UIAction::MoveBack(Turn::steps(1)));
+
+        let scene = &form.current_scene;
+        let Some(TimerMode::Taro(config)) = timer_mode(scene.scene, &scene.elements) else {
+            panic!("not a Taro mode");
+        };
+        assert_eq!(config.work_block, MIN_BLOCK);
+    }
+}
@@ -1,125 +1,71 @@
-use embedded_graphics::{pixelcolor::Rgb565, prelude::Point};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::{Point, Size}, primitives::Rectangle};
 
-use crate::{animations::{Animation, AnimationIterator, AnimationMetadata}, scenes_util::{ImageData, Scene, SceneData, UIType}};
+use crate::{list_menu::{ListMenu, MenuAction, MenuItem, Setting}, scenes_util::Scene, time_util::TimerMode};
 
-pub const DISPLAY_WIDTH: u32 = 320;
-pub const DISPLAY_HEIGHT: u32 = 240;
+// DISPLAY_WIDTH, DISPLAY_HEIGHT, MAX_ANIMATIONS, MAX_SCENE_ELEMENTS and LABEL_CAPACITY
+include!("limits.rs");
 
 pub const FRAME_RATE: u64 = 15;
 pub const PIXEL_COUNT: usize = DISPLAY_HEIGHT as usize * DISPLAY_WIDTH as usize; 
 pub const MAX_DIRTY_RECTS: usize = 4;
 pub const MERGE_THRESHOLD: i32 = 16;
 
+// Long-press progress ring, in the gap between the work timer's labels
+pub const HOLD_ARC_AREA: Rectangle = Rectangle::new(Point::new(149, 72), Size::new(22, 22));
+
 #[cfg(not(feature = "simulator"))]
 pub static PSRAM_ALLOCATOR: esp_alloc::EspHeap = esp_alloc::EspHeap::empty();
 
 #[cfg(not(feature = "simulator"))]
 pub const SPI_BUF_SIZE: usize = 3072;
 
+// Session history ring buffer: 8 x 4KiB sectors (~1360 records).
+// On hardware it gets the 'history' partition in partitions.csv, these have to match it.
+pub const HISTORY_SECTORS: u32 = 8;
 
+#[cfg(not(feature = "simulator"))]
+pub const HISTORY_FLASH_OFFSET: u32 = 0x3F_8000;
 
-pub const DICE_ANIMATION: AnimationMetadata = AnimationMetadata::new(
-    include_bytes!("./assets/dice_rgb565.bin"), 
-    110, 
-    75, 
-    24);
-
-pub const MIKU: AnimationMetadata = AnimationMetadata::new(
-    include_bytes!("./assets/miku.bin"),
-    150,
-    20,
-    10,
-    );
-
-pub const DICE_ITERATOR: AnimationIterator = AnimationIterator {
-    frame_bytes: &DICE_ANIMATION,
-    current_frame: 0,
-    position: Point::new(20, 80),
-    looping: true
-};
-
-pub const MIKU_ITERATOR: AnimationIterator = AnimationIterator {
-    frame_bytes: &MIKU,
-    current_frame: 0,
-    position: Point::new(160, 80),
-    looping: true
-};
+// The 'factory' app partition in partitions.csv
+#[cfg(not(feature = "simulator"))]
+const APP_PARTITION_END: u32 = 0x1_0000 + 0x3E_8000;
+#[cfg(not(feature = "simulator"))]
+const FLASH_SIZE: u32 = 0x40_0000;
 
-pub const MIKU_ITERATOR2: AnimationIterator = AnimationIterator {
-    frame_bytes: &MIKU,
-    current_frame: 0,
-    position: Point::new(160, 110),
-    looping: true
+#[cfg(not(feature = "simulator"))]
+const _: () = {
+    assert!(HISTORY_FLASH_OFFSET >= APP_PARTITION_END, "history overlaps the app partition");
+    assert!(HISTORY_FLASH_OFFSET + HISTORY_SECTORS * 4096 <= FLASH_SIZE, "history runs past the end of flash");
 };
 
-pub const MAX_ANIMATIONS: usize = 6;
-
-pub const TEST_SCENE: SceneData = SceneData {
-    scene: Scene::MainMenu,
-    elements: [
-        UIType::AnimatedSprite(Animation::Sprite(DICE_ITERATOR)),
-        UIType::AnimatedSprite(Animation::Sprite(MIKU_ITERATOR)),
-        UIType::AnimatedSprite(Animation::Sprite(MIKU_ITERATOR2)),
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-    ],
-    cursor_index: 0
-};
+#[cfg(feature = "simulator")]
+pub const HISTORY_FILE: &str = "timetool_history.bin";
 
-pub const EMPTY_SCENE: SceneData = SceneData {
-    scene: Scene::MainMenu,
-    elements: [
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-    ],
-    cursor_index: 0
-};
 
-pub const MENU_HEADER_DATA: &[u8] = include_bytes!("./assets/Menu_Header.bmp");
-pub const CLOCK_FACE_DATA: &[u8] = include_bytes!("./assets/Clock_Face.bmp");
+pub const MAIN_MENU_ITEMS: &[MenuItem] = &[
+    MenuItem::new("Count up", MenuAction::StartMode(TimerMode::CountingUp)),
+    MenuItem::new("Taro", MenuAction::OpenScene(Scene::ConfigTaro)),
+    MenuItem::new("Taro Plus", MenuAction::OpenScene(Scene::ConfigTaroPlus)),
+    MenuItem::new("Stats", MenuAction::OpenScene(Scene::Statistics)),
+    MenuItem::new("Settings", MenuAction::OpenScene(Scene::Settings)),
+];
 
-pub const HEADER_IMAGE: ImageData = ImageData::new(
-    MENU_HEADER_DATA, 
-    176, 
-    40, 
-    Point::new(138, 11)
+// The session steps its own copy of this to know what Select picks
+pub const MAIN_MENU: ListMenu = ListMenu::new(
+    Rectangle::new(Point::new(40, 10), Size::new(240, 220)),
+    MAIN_MENU_ITEMS
 );
 
-pub const CLOCK_IMAGE: ImageData = ImageData::new(
-    CLOCK_FACE_DATA, 
-    115, 
-    229, 
-    Point::new(10, 10)
-);
+pub const SETTINGS_ITEMS: &[MenuItem] = &[
+    MenuItem::new("Resolution", MenuAction::ToggleSetting(Setting::Resolution)),
+    MenuItem::new("Back", MenuAction::OpenScene(Scene::MainMenu)),
+];
 
-pub const MAIN_MENU_SCENE: SceneData = SceneData {
-    scene: Scene::MainMenu,
-    elements: [
-        UIType::Image(HEADER_IMAGE),
-        UIType::Image(CLOCK_IMAGE),
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-        UIType::Empty,
-    ],
-    cursor_index: 0
-};
+pub const SETTINGS_MENU: ListMenu = ListMenu::new(
+    Rectangle::new(Point::new(40, 10), Size::new(240, 220)),
+    SETTINGS_ITEMS
+);
 
 pub const RGB_DEEP_PURPLE: Rgb565 = Rgb565::new(61, 56, 70);
+// Amber, for paused time on the timer screen and in statistics
+pub const PAUSE_COLOR: Rgb565 = Rgb565::new(31, 45, 0);
@@ -0,0 +1,246 @@
+use embassy_time::Duration;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Size},
+    primitives::{Primitive, PrimitiveStyle, Rectangle},
+    Drawable,
+};
+
+use crate::scenes_util::{DigitsElement, SceneData, UIAction, UINode};
+
+// Space between digits, the colon gets a slot of its own between HH and MM
+const DIGIT_GAP: u32 = 4;
+const COLON_WIDTH: u32 = 12;
+// Bar under the digit being edited
+const UNDERLINE_HEIGHT: u32 = 3;
+// What one step of each digit is worth in minutes
+const DIGIT_MINUTES: [u32; 4] = [600, 60, 10, 1];
+// 99:59, the most four digits can show
+const MAX_MINUTES: u32 = 99 * 60 + 59;
+
+/*
+ * "HH:MM" made of four DigitsElements.
+ * Turning changes the digit being edited, carrying into the others like a clock
+ * and taking encoder acceleration into account. Select moves on to the next digit
+ * and Back to the previous one; past either end focus leaves the editor.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct DurationEditor {
+    pub position: Rectangle,
+    digits: [DigitsElement; 4],
+    // Digit being edited, 0 is the tens of hours
+    editing: u8,
+    next_element: u8,
+    prev_element: u8
+}
+
+impl DurationEditor {
+    // Anything past 99:59 is shown as 99:59
+    pub const fn new(position: Rectangle, duration: Duration, next_element: u8, prev_element: u8) -> Self {
+        let mut digits = [DigitsElement::new(position, 0, next_element, prev_element); 4];
+        let mut index = 0;
+        while index < 4 {
+            digits[index] = DigitsElement::new(Self::digit_rect(position, index), 0, next_element, prev_element);
+            index += 1;
+        }
+        // Tens of minutes only go up to 5
+        digits[2] = digits[2].with_limit(6);
+
+        let minutes = duration.as_secs() / 60;
+        let minutes = if minutes > MAX_MINUTES as u64 { MAX_MINUTES } else { minutes as u32 };
+        let mut editor = DurationEditor { position, digits, editing: 0, next_element, prev_element };
+        editor.set_minutes(minutes);
+        editor
+    }
+
+    pub const fn with_color(mut self, color: Rgb565) -> Self {
+        let mut index = 0;
+        while index < 4 {
+            self.digits[index] = self.digits[index].with_color(color);
+            index += 1;
+        }
+        self
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.minutes() as u64 * 60)
+    }
+
+    fn minutes(&self) -> u32 {
+        let [hours_tens, hours, minutes_tens, minutes] = self.digits.map(|digit| digit.current_digit as u32);
+        (hours_tens * 10 + hours) * 60 + minutes_tens * 10 + minutes
+    }
+
+    const fn set_minutes(&mut self, minutes: u32) {
+        let (hours, minutes) = (minutes / 60, minutes % 60);
+        self.digits[0].current_digit = (hours / 10) as u8;
+        self.digits[1].current_digit = (hours % 10) as u8;
+        self.digits[2].current_digit = (minutes / 10) as u8;
+        self.digits[3].current_digit = (minutes % 10) as u8;
+    }
+
+    // Digits keep DIGIT_GAP clear of the edges so the focus highlight doesn't cover them
+    const fn digit_rect(position: Rectangle, index: usize) -> Rectangle {
+        let width = position.size.width.saturating_sub(COLON_WIDTH + 6 * DIGIT_GAP) / 4;
+        let height = position.size.height.saturating_sub(UNDERLINE_HEIGHT + 3 * DIGIT_GAP);
+        let colon = if index >= 2 { COLON_WIDTH + DIGIT_GAP } else { 0 };
+        let x = position.top_left.x + (DIGIT_GAP + index as u32 * (width + DIGIT_GAP) + colon) as i32;
+
+        Rectangle::new(Point::new(x, position.top_left.y + DIGIT_GAP as i32), Size::new(width, height))
+    }
+
+    fn draw_colon<D: DrawTarget<Color = Rgb565>>(&self, target: &mut D) -> Result<(), D::Error> {
+        let hours = self.digits[1].position;
+        let dot = Size::new(COLON_WIDTH / 2, COLON_WIDTH / 2);
+        let x = hours.top_left.x + (hours.size.width + DIGIT_GAP + COLON_WIDTH / 2) as i32;
+        let style = PrimitiveStyle::with_fill(self.digits[1].color);
+
+        for y in [hours.size.height / 3, hours.size.height * 2 / 3] {
+            Rectangle::with_center(Point::new(x, hours.top_left.y + y as i32), dot)
+                .into_styled(style)
+                .draw(target)?;
+        }
+        Ok(())
+    }
+}
+
+impl UINode for DurationEditor {
+    fn get_position(&self) -> &Rectangle {
+        &self.position
+    }
+
+    fn handle_action(&mut self, scene: &mut SceneData, action: UIAction) {
+        match action {
+            // The whole duration moves, so turning past :59 carries into the hours
+            UIAction::MoveNext(turn) => {
+                let step = DIGIT_MINUTES[self.editing as usize] * turn.accelerated as u32;
+                self.set_minutes((self.minutes() + step).min(MAX_MINUTES));
+            }
+            UIAction::MoveBack(turn) => {
+                let step = DIGIT_MINUTES[self.editing as usize] * turn.accelerated as u32;
+                self.set_minutes(self.minutes().saturating_sub(step));
+            }
+            UIAction::Select => {
+                if self.editing < 3 {
+                    self.editing += 1;
+                } else {
+                    // Start from the first digit next time focus comes back
+                    self.editing = 0;
+                    scene.cursor_index = self.next_element;
+                }
+            }
+            UIAction::Back => {
+                if self.editing > 0 {
+                    self.editing -= 1;
+                } else {
+                    scene.cursor_index = self.prev_element;
+                }
+            }
+            UIAction::Undo | UIAction::Home => {}
+        }
+    }
+}
+
+impl Drawable for DurationEditor {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        for digit in &self.digits {
+            digit.draw(target)?;
+        }
+        self.draw_colon(target)?;
+
+        let editing = self.digits[self.editing as usize];
+        Rectangle::new(
+            editing.position.top_left + Point::new(0, (editing.position.size.height + DIGIT_GAP) as i32),
+            Size::new(editing.position.size.width, UNDERLINE_HEIGHT)
+        )
+        .into_styled(PrimitiveStyle::with_fill(editing.color))
+        .draw(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenes_util::Turn;
+
+    const AREA: Rectangle = Rectangle::new(Point::new(0, 0), Size::new(160, 60));
+    const NEXT: u8 = 7;
+    const PREV: u8 = 3;
+
+    fn editor(minutes: u64) -> DurationEditor {
+        DurationEditor::new(AREA, Duration::from_secs(minutes * 60), NEXT, PREV)
+    }
+
+    // Runs the actions against a scene focused on the editor, hands back where focus ended up
+    fn apply(editor: &mut DurationEditor, actions: &[UIAction]) -> u8 {
+        let mut scene = SceneData { cursor_index: 5, ..SceneData::default() };
+        for action in actions {
+            editor.handle_action(&mut scene, *action);
+        }
+        scene.cursor_index
+    }
+
+    fn digits(editor: &DurationEditor) -> [u8; 4] {
+        editor.digits.map(|digit| digit.current_digit)
+    }
+
+    #[test]
+    fn starts_at_the_given_duration() {
+        assert_eq!(digits(&editor(90)), [0, 1, 3, 0]);
+        assert_eq!(editor(90).duration(), Duration::from_secs(90 * 60));
+        // Past what four digits can show
+        assert_eq!(digits(&editor(200 * 60)), [9, 9, 5, 9]);
+    }
+
+    #[test]
+    fn minutes_carry_into_the_hours() {
+        let mut editor = editor(55);
+        apply(&mut editor, &[UIAction::Select, UIAction::Select, UIAction::Select]);
+        apply(&mut editor, &[UIAction::MoveNext(Turn::steps(7))]);
+        assert_eq!(digits(&editor), [0, 1, 0, 2]);
+
+        // And borrow back out of them
+        apply(&mut editor, &[UIAction::MoveBack(Turn::steps(3))]);
+        assert_eq!(digits(&editor), [0, 0, 5, 9]);
+    }
+
+    #[test]
+    fn tens_of_minutes_carry_past_the_hour() {
+        let mut editor = editor(50);
+        apply(&mut editor, &[UIAction::Select, UIAction::Select, UIAction::MoveNext(Turn::steps(2))]);
+        assert_eq!(digits(&editor), [0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn clamps_at_both_ends() {
+        let mut editor = editor(99 * 60 + 50);
+        apply(&mut editor, &[UIAction::MoveNext(Turn::steps(1))]);
+        assert_eq!(digits(&editor), [9, 9, 5, 9]);
+
+        apply(&mut editor, &[UIAction::MoveBack(Turn::steps(20))]);
+        assert_eq!(digits(&editor), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn acceleration_multiplies_the_step() {
+        let mut editor = editor(0);
+        apply(&mut editor, &[UIAction::Select, UIAction::Select, UIAction::Select]);
+        apply(&mut editor, &[UIAction::MoveNext(Turn { detents: 2, accelerated: 15 })]);
+        assert_eq!(digits(&editor), [0, 0, 1, 5]);
+    }
+
+    #[test]
+    fn focus_leaves_past_either_end() {
+        let mut editor = editor(0);
+        assert_eq!(apply(&mut editor, &[UIAction::Back]), PREV);
+        let select = [UIAction::Select; 4];
+        assert_eq!(apply(&mut editor, &select), NEXT);
+        // Editing starts from the first digit again
+        assert_eq!(editor.editing, 0);
+    }
+}
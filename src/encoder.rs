@@ -1,108 +1,308 @@
-use embassy_time::{Duration, Timer};
-use esp_hal::gpio::Input;
-use crate::scenes_util::UIAction;
-
-pub struct RotaryEncoder<'a> {
-    pin_a: Input<'a>,
-    pin_b: Input<'a>,
-    last_a: bool,
-    last_b: bool,
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Instant, Timer};
+use embedded_hal::digital::InputPin;
+use embedded_hal_async::digital::Wait;
+use crate::scenes_util::{Turn, UIAction};
+
+/*
+ * Quadrature encoder decoded with a Gray-code state table.
+ * Every edge on A or B is checked against the previous AB state, so contact bounce
+ * cancels itself out (+1 then -1) and transitions that skip a state are dropped.
+ * Works with any embedded-hal pins, e.g. esp-hal Inputs or mocks on the host.
+ */
+pub struct RotaryEncoder<A, B> {
+    pin_a: A,
+    pin_b: B,
+    // Last AB reading, A in bit 1 and B in bit 0
+    state: u8,
+    // Valid transitions since the last full detent
+    partial: i8,
+    steps_per_detent: StepsPerDetent,
 }
 
-const DEBOUNCE_DELAY: Duration = Duration::from_micros(500);
+// Edges closer together than this belong to the same turn
+const QUIET_TIME: Duration = Duration::from_millis(5);
+
+// Quadrature transitions an encoder goes through between two clicks
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StepsPerDetent {
+    One,
+    Two,
+    #[default]
+    Four,
+}
+
+impl StepsPerDetent {
+    const fn transitions(self) -> i8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+            Self::Four => 4,
+        }
+    }
+}
+
+/*
+ * Direction of a move from the state in the upper two bits to the state in the lower two.
+ * +1 clockwise (00 -> 10 -> 11 -> 01 -> 00), -1 counter-clockwise,
+ * 0 for no change or an invalid jump where both pins changed at once,
+ * there's no telling which way a jump went so it's dropped and the state resyncs.
+ */
+const TRANSITIONS: [i8; 16] = [
+     0, -1,  1,  0,
+     1,  0,  0, -1,
+    -1,  0,  0,  1,
+     0,  1, -1,  0,
+];
+
+impl<A: InputPin + Wait, B: InputPin + Wait> RotaryEncoder<A, B> {
+    pub fn new(mut pin_a: A, mut pin_b: B) -> Self {
+        let state = read_state(&mut pin_a, &mut pin_b);
 
-impl<'a> RotaryEncoder<'a> {
-    pub fn new(pin_a: Input<'a>, pin_b: Input<'a>) -> Self {
-        let last_a = pin_a.is_high();
-        let last_b = pin_b.is_high();
-        
         Self {
             pin_a,
             pin_b,
-            last_a,
-            last_b,
+            state,
+            partial: 0,
+            steps_per_detent: StepsPerDetent::default(),
         }
     }
 
-    /// Wait for encoder rotation and return the corresponding UIAction
-    pub async fn wait_for_rotation(&mut self) -> UIAction {
+    pub fn with_steps_per_detent(self, steps_per_detent: StepsPerDetent) -> Self {
+        Self { steps_per_detent, partial: 0, ..self }
+    }
+
+    /// Wait until the encoder has moved at least one detent, then keep counting
+    /// until the pins have been quiet for QUIET_TIME.
+    /// Returns the signed number of detents, positive is clockwise.
+    pub async fn wait_for_steps(&mut self) -> i16 {
+        let mut steps: i16 = 0;
         loop {
-            // Wait for any edge on pin A
-            self.pin_a.wait_for_any_edge().await;
-            Timer::after(DEBOUNCE_DELAY).await;
-            
-            let current_a = self.pin_a.is_high();
-            let current_b = self.pin_b.is_high();
-            
-            // Only process if A actually changed (debounce check)
-            if current_a != self.last_a {
-                let direction = if current_a {
-                    // A rising edge: check B to determine direction
-                    if !current_b {
-                        Some(RotationDirection::Clockwise)
-                    } else {
-                        Some(RotationDirection::CounterClockwise)
-                    }
-                } else {
-                    // A falling edge: check B (inverted logic)
-                    if current_b {
-                        Some(RotationDirection::Clockwise)
-                    } else {
-                        Some(RotationDirection::CounterClockwise)
-                    }
-                };
-                
-                self.last_a = current_a;
-                self.last_b = current_b;
-                
-                if let Some(dir) = direction {
-                    return dir.into();
-                }
+            let edge = select(self.pin_a.wait_for_any_edge(), self.pin_b.wait_for_any_edge());
+            if steps == 0 {
+                let _ = edge.await;
+            } else if let Either::Second(()) = select(edge, Timer::after(QUIET_TIME)).await {
+                return steps;
             }
+
+            steps = steps.saturating_add(self.poll_steps());
         }
     }
 
-    /// Non-blocking check for rotation (returns None if no rotation detected)
-    pub fn poll_rotation(&mut self) -> Option<UIAction> {
-        let current_a = self.pin_a.is_high();
-        let current_b = self.pin_b.is_high();
-        
-        if current_a != self.last_a {
-            let direction = if current_a {
-                if !current_b {
-                    Some(RotationDirection::Clockwise)
-                } else {
-                    Some(RotationDirection::CounterClockwise)
-                }
-            } else {
-                if current_b {
-                    Some(RotationDirection::Clockwise)
-                } else {
-                    Some(RotationDirection::CounterClockwise)
-                }
-            };
-            
-            self.last_a = current_a;
-            self.last_b = current_b;
-            
-            return direction.map(|d| d.into());
-        }
-        
-        None
+    /// Non-blocking: read the pins once and return the detents completed since the last call
+    pub fn poll_steps(&mut self) -> i16 {
+        let next = read_state(&mut self.pin_a, &mut self.pin_b);
+        let direction = TRANSITIONS[((self.state << 2) | next) as usize];
+        self.state = next;
+
+        self.partial += direction;
+        let transitions = self.steps_per_detent.transitions();
+        let steps = self.partial / transitions;
+        self.partial %= transitions;
+        steps as i16
     }
 }
 
+#[inline]
+fn read_state<A: InputPin, B: InputPin>(pin_a: &mut A, pin_b: &mut B) -> u8 {
+    // A pin that can't be read counts as low
+    let a = pin_a.is_high().unwrap_or(false) as u8;
+    let b = pin_b.is_high().unwrap_or(false) as u8;
+    (a << 1) | b
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RotationDirection {
     Clockwise,
     CounterClockwise,
 }
 
-impl From<RotationDirection> for UIAction {
-    fn from(direction: RotationDirection) -> Self {
-        match direction {
-            RotationDirection::Clockwise => UIAction::MoveNext,
-            RotationDirection::CounterClockwise => UIAction::MoveBack,
+impl RotationDirection {
+    pub const fn from_steps(steps: i16) -> Option<Self> {
+        match steps {
+            0 => None,
+            1.. => Some(Self::Clockwise),
+            _ => Some(Self::CounterClockwise),
+        }
+    }
+
+    pub const fn action(self, turn: Turn) -> UIAction {
+        match self {
+            Self::Clockwise => UIAction::MoveNext(turn),
+            Self::CounterClockwise => UIAction::MoveBack(turn),
         }
     }
 }
+
+/*
+ * Multiplier used when detents come in quickly.
+ * A detent arriving within 'max_interval' of the previous one counts 'multiplier' times.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccelerationStage {
+    pub max_interval: Duration,
+    pub multiplier: u8,
+}
+
+// Stages ordered fastest first, the first one that matches wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccelerationCurve(pub &'static [AccelerationStage]);
+
+impl AccelerationCurve {
+    pub const NONE: Self = AccelerationCurve(&[]);
+
+    // Slow turns step by 1, quick turns by 5, fast spins by 10
+    pub const DEFAULT: Self = AccelerationCurve(&[
+        AccelerationStage { max_interval: Duration::from_millis(30), multiplier: 10 },
+        AccelerationStage { max_interval: Duration::from_millis(80), multiplier: 5 },
+    ]);
+}
+
+impl Default for AccelerationCurve {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/*
+ * Scales detent counts from RotaryEncoder by how fast the knob is turning.
+ * Changing direction always starts again at 1x.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Accelerator {
+    curve: AccelerationCurve,
+    // Time and direction of the last detent
+    last: Option<(Instant, bool)>,
+}
+
+impl Accelerator {
+    pub const fn new(curve: AccelerationCurve) -> Self {
+        Accelerator { curve, last: None }
+    }
+
+    pub fn apply(&mut self, steps: i16, now: Instant) -> i16 {
+        if steps == 0 {
+            return 0;
+        }
+
+        let clockwise = steps > 0;
+        let interval = match self.last {
+            Some((last, last_clockwise)) if last_clockwise == clockwise => {
+                Some((now - last) / steps.unsigned_abs() as u32)
+            }
+            _ => None
+        };
+        self.last = Some((now, clockwise));
+
+        let multiplier = interval
+            .and_then(|interval| self.curve.0.iter().find(|stage| interval <= stage.max_interval))
+            .map_or(1, |stage| stage.multiplier);
+        steps.saturating_mul(multiplier as i16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::{block_on, join::join};
+    use embassy_time::Timer;
+
+    use super::*;
+    use crate::mock_pin::MockPin;
+
+    // AB states of one clockwise detent, starting from 00
+    const CLOCKWISE: [u8; 4] = [0b10, 0b11, 0b01, 0b00];
+
+    fn transition(from: u8, to: u8) -> i8 {
+        TRANSITIONS[((from << 2) | to) as usize]
+    }
+
+    fn encoder() -> (RotaryEncoder<MockPin, MockPin>, MockPin, MockPin) {
+        let (a, b) = (MockPin::default(), MockPin::default());
+        (RotaryEncoder::new(a.clone(), b.clone()), a, b)
+    }
+
+    fn set(a: &MockPin, b: &MockPin, state: u8) {
+        a.set(state & 0b10 != 0);
+        b.set(state & 0b01 != 0);
+    }
+
+    #[test]
+    fn transitions_follow_gray_code() {
+        let mut from = 0b00;
+        for to in CLOCKWISE {
+            assert_eq!(transition(from, to), 1, "{from:02b} -> {to:02b}");
+            assert_eq!(transition(to, from), -1, "{to:02b} -> {from:02b}");
+            from = to;
+        }
+    }
+
+    #[test]
+    fn transitions_ignore_no_change_and_invalid_jumps() {
+        for state in 0..4 {
+            assert_eq!(transition(state, state), 0);
+            // Both pins flipped at once
+            assert_eq!(transition(state, state ^ 0b11), 0);
+        }
+    }
+
+    #[test]
+    fn transitions_cancel_out_bounce() {
+        // Contact on A chattering between 00 and 10
+        let bounce = [0b10, 0b00, 0b10, 0b00, 0b10];
+        let mut from = 0b00;
+        let total: i8 = bounce.iter().map(|&to| {
+            let direction = transition(from, to);
+            from = to;
+            direction
+        }).sum();
+        assert_eq!(total, 1);
+    }
+
+    #[test]
+    fn poll_steps_counts_full_detents() {
+        let (mut encoder, a, b) = encoder();
+        let mut steps = 0;
+        for state in CLOCKWISE.iter().chain(CLOCKWISE.iter()) {
+            set(&a, &b, *state);
+            steps += encoder.poll_steps();
+        }
+        assert_eq!(steps, 2);
+
+        for state in CLOCKWISE.iter().rev().skip(1).chain([&0b00]) {
+            set(&a, &b, *state);
+            steps += encoder.poll_steps();
+        }
+        assert_eq!(steps, 1);
+    }
+
+    #[test]
+    fn poll_steps_drops_both_pin_jumps() {
+        let (encoder, a, b) = encoder();
+        let mut encoder = encoder.with_steps_per_detent(StepsPerDetent::One);
+        // Glitches where both pins flip at once, in and out of a turn
+        let mut steps = 0;
+        for state in [0b10, 0b01, 0b10, 0b01, 0b10] {
+            set(&a, &b, state);
+            steps += encoder.poll_steps();
+        }
+        // Only the first 00 -> 10 is a real transition
+        assert_eq!(steps, 1);
+
+        // Picks up from the state it resynced to
+        set(&a, &b, 0b11);
+        assert_eq!(encoder.poll_steps(), 1);
+    }
+
+    #[test]
+    fn wait_for_steps_gathers_a_burst() {
+        let (mut encoder, a, b) = encoder();
+        let turn = async {
+            for state in CLOCKWISE.iter().cycle().take(8) {
+                Timer::after(Duration::from_millis(1)).await;
+                set(&a, &b, *state);
+            }
+        };
+        let (steps, ()) = block_on(join(encoder.wait_for_steps(), turn));
+        assert_eq!(steps, 2);
+    }
+}
@@ -0,0 +1,302 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Size},
+    primitives::{PointsIter, Rectangle},
+    text::{Alignment, Baseline},
+    Pixel,
+};
+use embedded_graphics_framebuf::FrameBuf;
+
+use crate::{buffer_backend::BufferData, color_mixing::alpha_blending::RGBa};
+
+/*
+ * Scientia-Black pre-rasterized by build.rs, see generate_glyph_atlas there for the layout.
+ * Same bytes on hardware and in the simulator, so text looks identical on both.
+ */
+pub static ATLAS: GlyphAtlas = GlyphAtlas(include_bytes!(concat!(env!("OUT_DIR"), "/glyph_atlas.bin")));
+
+const SIZE_HEADER_LEN: usize = 20;
+const GLYPH_LEN: usize = 13;
+const KERNING_LEN: usize = 5;
+
+pub struct GlyphAtlas(&'static [u8]);
+
+impl GlyphAtlas {
+    fn size_count(&self) -> usize {
+        match self.0.get(..5) {
+            Some([b'G', b'L', b'Y', b'A', 1]) => self.0[5] as usize,
+            _ => 0,
+        }
+    }
+
+    pub fn sizes(&self) -> impl Iterator<Item = AtlasFont> + '_ {
+        (0..self.size_count()).map(|index| AtlasFont::parse(self.0, 6 + index * SIZE_HEADER_LEN))
+    }
+
+    // Largest baked size that isn't bigger than asked for, the smallest one otherwise
+    pub fn font(&self, pixel_size: u32) -> Option<AtlasFont> {
+        self.sizes()
+            .filter(|font| font.pixel_size as u32 <= pixel_size)
+            .max_by_key(|font| font.pixel_size)
+            .or_else(|| self.sizes().min_by_key(|font| font.pixel_size))
+    }
+}
+
+// One pixel size out of the atlas
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasFont {
+    data: &'static [u8],
+    pub pixel_size: u8,
+    pub ascent: i8,
+    pub descent: i8,
+    pub line_gap: i8,
+    glyph_count: usize,
+    kerning_count: usize,
+    glyphs: usize,
+    kerning: usize,
+    bitmaps: usize,
+}
+
+impl AtlasFont {
+    fn parse(data: &'static [u8], header: usize) -> Self {
+        let u16_at = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+        let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]) as usize;
+
+        AtlasFont {
+            data,
+            pixel_size: data[header],
+            ascent: data[header + 1] as i8,
+            descent: data[header + 2] as i8,
+            line_gap: data[header + 3] as i8,
+            glyph_count: u16_at(header + 4),
+            kerning_count: u16_at(header + 6),
+            glyphs: u32_at(header + 8),
+            kerning: u32_at(header + 12),
+            bitmaps: u32_at(header + 16),
+        }
+    }
+
+    pub fn line_height(&self) -> u32 {
+        (self.ascent as i32 - self.descent as i32 + self.line_gap as i32).max(0) as u32
+    }
+
+    // Glyphs are sorted by codepoint
+    fn glyph_index(&self, c: char) -> Option<usize> {
+        let codepoint_at = |index: usize| {
+            let at = self.glyphs + index * GLYPH_LEN;
+            u32::from_le_bytes([self.data[at], self.data[at + 1], self.data[at + 2], self.data[at + 3]])
+        };
+
+        let (mut low, mut high) = (0, self.glyph_count);
+        while low < high {
+            let middle = (low + high) / 2;
+            match codepoint_at(middle).cmp(&(c as u32)) {
+                core::cmp::Ordering::Equal => return Some(middle),
+                core::cmp::Ordering::Less => low = middle + 1,
+                core::cmp::Ordering::Greater => high = middle,
+            }
+        }
+        None
+    }
+
+    fn glyph_at(&self, index: usize) -> Glyph {
+        let at = self.glyphs + index * GLYPH_LEN;
+        let record = &self.data[at..at + GLYPH_LEN];
+        let offset = u32::from_le_bytes([record[4], record[5], record[6], record[7]]) as usize;
+        let (width, height) = (record[9], record[10]);
+        let len = (width as usize * height as usize).div_ceil(2);
+
+        Glyph {
+            advance: record[8],
+            width,
+            height,
+            x_offset: record[11] as i8,
+            y_offset: record[12] as i8,
+            bitmap: &self.data[self.bitmaps + offset..self.bitmaps + offset + len],
+        }
+    }
+
+    pub fn glyph(&self, c: char) -> Option<Glyph> {
+        self.glyph_index(c).map(|index| self.glyph_at(index))
+    }
+
+    // Pen adjustment between two glyphs, pairs are sorted by (left, right)
+    fn kerning(&self, left: usize, right: usize) -> i8 {
+        let pair_at = |index: usize| {
+            let at = self.kerning + index * KERNING_LEN;
+            let record = &self.data[at..at + KERNING_LEN];
+            let pair = (u16::from_le_bytes([record[0], record[1]]) as usize, u16::from_le_bytes([record[2], record[3]]) as usize);
+            (pair, record[4] as i8)
+        };
+
+        let (mut low, mut high) = (0, self.kerning_count);
+        while low < high {
+            let middle = (low + high) / 2;
+            let (pair, amount) = pair_at(middle);
+            match pair.cmp(&(left, right)) {
+                core::cmp::Ordering::Equal => return amount,
+                core::cmp::Ordering::Less => low = middle + 1,
+                core::cmp::Ordering::Greater => high = middle,
+            }
+        }
+        0
+    }
+
+    // Every character with the pen position it starts at and how far it moves the pen
+    fn advances<'t>(&self, text: &'t str) -> impl Iterator<Item = (Option<Glyph>, i32, i32)> + 't {
+        let font = *self;
+        let mut pen = 0i32;
+        let mut previous: Option<usize> = None;
+
+        text.chars().map(move |c| {
+            let Some(index) = font.glyph_index(c) else {
+                // Not baked, leave a gap the width of a space
+                let gap = font.glyph(' ').map_or(font.pixel_size as i32 / 3, |space| space.advance as i32);
+                previous = None;
+                pen += gap;
+                return (None, pen - gap, gap);
+            };
+            if let Some(previous) = previous {
+                pen += font.kerning(previous, index) as i32;
+            }
+            previous = Some(index);
+
+            let glyph = font.glyph_at(index);
+            pen += glyph.advance as i32;
+            (Some(glyph), pen - glyph.advance as i32, glyph.advance as i32)
+        })
+    }
+
+    fn layout<'t>(&self, text: &'t str) -> impl Iterator<Item = (Glyph, i32)> + 't {
+        self.advances(text).filter_map(|(glyph, x, _)| glyph.map(|glyph| (glyph, x)))
+    }
+
+    pub fn measure(&self, text: &str) -> u32 {
+        self.advances(text)
+            .last()
+            .map_or(0, |(_, x, advance)| (x + advance).max(0) as u32)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Glyph {
+    pub advance: u8,
+    pub width: u8,
+    pub height: u8,
+    // Top left of the bitmap, relative to the pen on the baseline
+    pub x_offset: i8,
+    pub y_offset: i8,
+    bitmap: &'static [u8],
+}
+
+impl Glyph {
+    // Coverage scaled up to 0-255
+    pub fn alpha(&self, x: u32, y: u32) -> u8 {
+        let index = (y * self.width as u32 + x) as usize;
+        let byte = self.bitmap[index / 2];
+        // Two pixels per byte, the first one in the high nibble
+        let shift = if index & 1 == 0 { 4 } else { 0 };
+        (byte >> shift & 0x0F) * 17
+    }
+
+    fn area(&self, pen: Point) -> Rectangle {
+        Rectangle::new(
+            pen + Point::new(self.x_offset as i32, self.y_offset as i32),
+            Size::new(self.width as u32, self.height as u32),
+        )
+    }
+}
+
+/*
+ * Text style drawing from the atlas,
+ * position/alignment/baseline mean the same as for embedded-graphics Text
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasTextStyle {
+    pub font: AtlasFont,
+    pub color: Rgb565,
+    pub alignment: Alignment,
+    pub baseline: Baseline,
+}
+
+impl AtlasTextStyle {
+    pub fn new(font: AtlasFont, color: Rgb565) -> Self {
+        AtlasTextStyle { font, color, alignment: Alignment::Left, baseline: Baseline::Top }
+    }
+
+    pub fn with_alignment(self, alignment: Alignment) -> Self {
+        AtlasTextStyle { alignment, ..self }
+    }
+
+    pub fn with_baseline(self, baseline: Baseline) -> Self {
+        AtlasTextStyle { baseline, ..self }
+    }
+
+    // Pen position on the baseline where the first glyph starts
+    fn origin(&self, text: &str, position: Point) -> Point {
+        let font = &self.font;
+        let x = match self.alignment {
+            Alignment::Left => position.x,
+            Alignment::Center => position.x - font.measure(text) as i32 / 2,
+            Alignment::Right => position.x - font.measure(text) as i32,
+        };
+        let y = match self.baseline {
+            Baseline::Top => position.y + font.ascent as i32,
+            Baseline::Middle => position.y + (font.ascent as i32 + font.descent as i32) / 2,
+            Baseline::Bottom => position.y + font.descent as i32,
+            Baseline::Alphabetic => position.y,
+        };
+        Point::new(x, y)
+    }
+
+    pub fn bounding_box(&self, text: &str, position: Point) -> Rectangle {
+        let origin = self.origin(text, position);
+        Rectangle::new(
+            Point::new(origin.x, origin.y - self.font.ascent as i32),
+            Size::new(self.font.measure(text), (self.font.ascent as i32 - self.font.descent as i32) as u32),
+        )
+    }
+
+    // Blends over whatever is already in the frame buffer, returns the area to mark dirty
+    pub fn draw(&self, text: &str, position: Point, clip: &Rectangle, frame_buffer: &mut FrameBuf<Rgb565, BufferData>) -> Rectangle {
+        let origin = self.origin(text, position);
+
+        for (glyph, x) in self.font.layout(text) {
+            let area = glyph.area(origin + Point::new(x, 0));
+            let visible = area.intersection(clip);
+            if visible.is_zero_sized() {
+                continue;
+            }
+
+            let pixels = visible.points().map(|point| {
+                let local = point - area.top_left;
+                (self.color, glyph.alpha(local.x as u32, local.y as u32))
+            });
+            frame_buffer.data.blend_iter(visible.top_left, visible.size.width, visible.size.height, pixels);
+        }
+
+        self.bounding_box(text, position).intersection(clip)
+    }
+
+    // Blends over a known solid colour instead, for targets that can't be read back
+    pub fn draw_over<D>(&self, text: &str, position: Point, background: Rgb565, target: &mut D) -> Result<Rectangle, D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let origin = self.origin(text, position);
+
+        for (glyph, x) in self.font.layout(text) {
+            let area = glyph.area(origin + Point::new(x, 0));
+            let pixels = area.points().filter_map(|point| {
+                let local = point - area.top_left;
+                match glyph.alpha(local.x as u32, local.y as u32) {
+                    0 => None,
+                    alpha => Some(Pixel(point, RGBa::new(self.color, alpha).blend(background))),
+                }
+            });
+            target.draw_iter(pixels)?;
+        }
+
+        Ok(self.bounding_box(text, position))
+    }
+}
@@ -0,0 +1,717 @@
+use core::fmt::Debug;
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use embassy_time::Instant;
+
+use crate::payloads::SessionState;
+use crate::time_util::TimerAdjust;
+
+#[cfg(not(feature = "simulator"))]
+pub use flash_impl::FlashLogStorage;
+#[cfg(feature = "simulator")]
+pub use file_impl::FileLogStorage;
+
+// ---------------------------------------------------
+// Storage backend, selected by feature
+// ---------------------------------------------------
+#[cfg(not(feature = "simulator"))]
+pub type HistoryStorage = FlashLogStorage<esp_storage::FlashStorage<'static>>;
+
+#[cfg(feature = "simulator")]
+pub type HistoryStorage = FileLogStorage;
+
+pub type SessionHistory = Mutex<CriticalSectionRawMutex, HistoryLog<HistoryStorage>>;
+
+/*
+ * Byte-addressed storage split into equally sized erase sectors.
+ * Mirrors NOR flash: erased bytes read back as 0xFF and
+ * a byte is only written once between erases.
+ */
+pub trait LogStorage {
+    type Error: Debug;
+
+    // Smallest erasable unit in bytes
+    const SECTOR_SIZE: u32;
+
+    fn sector_count(&self) -> u32;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error>;
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    fn erase_sector(&mut self, sector: u32) -> Result<(), Self::Error>;
+}
+
+#[derive(Debug)]
+pub enum HistoryError<E> {
+    Storage(E),
+    // Every slot within a sector of the head stayed written, e.g. an erase that didn't take
+    NoFreeSlot,
+}
+
+impl<E> From<E> for HistoryError<E> {
+    fn from(e: E) -> Self {
+        HistoryError::Storage(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalKind {
+    Work,
+    Break,
+    Pause
+}
+
+impl IntervalKind {
+    pub fn from_state(state: SessionState) -> Option<Self> {
+        match state {
+            SessionState::Working => Some(Self::Work),
+            SessionState::Break => Some(Self::Break),
+            SessionState::Paused => Some(Self::Pause),
+            _ => None
+        }
+    }
+
+    const fn to_byte(self) -> u8 {
+        match self {
+            Self::Work => 0,
+            Self::Break => 1,
+            Self::Pause => 2
+        }
+    }
+
+    const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Work),
+            1 => Some(Self::Break),
+            2 => Some(Self::Pause),
+            _ => None
+        }
+    }
+}
+
+// One finished work or break interval, timestamps in seconds on the log's timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionRecord {
+    pub kind: IntervalKind,
+    pub start: u32,
+    pub end: u32,
+    pub duration: u32
+}
+
+/*
+ * On-storage layout, little-endian, 24 bytes:
+ *  0..2   magic
+ *  2      kind
+ *  3      reserved (0)
+ *  4..8   sequence number
+ *  8..12  start
+ *  12..16 end
+ *  16..20 duration
+ *  20..24 CRC-32 of bytes 0..20
+ */
+pub const RECORD_SIZE: usize = 24;
+const RECORD_MAGIC: u16 = 0x5417;
+const ERASED: u8 = 0xFF;
+
+enum Slot {
+    Empty,
+    Valid(u32, SessionRecord),
+    // Torn write (power loss) or bit rot
+    Corrupted
+}
+
+impl SessionRecord {
+    pub fn new(kind: IntervalKind, start: u32, end: u32) -> Self {
+        SessionRecord {
+            kind,
+            start,
+            end,
+            duration: end.saturating_sub(start)
+        }
+    }
+
+    fn encode(&self, sequence: u32) -> [u8; RECORD_SIZE] {
+        let mut bytes = [0u8; RECORD_SIZE];
+        bytes[0..2].copy_from_slice(&RECORD_MAGIC.to_le_bytes());
+        bytes[2] = self.kind.to_byte();
+        bytes[4..8].copy_from_slice(&sequence.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.start.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.end.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.duration.to_le_bytes());
+        let crc = crc32(&bytes[..20]);
+        bytes[20..24].copy_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    fn decode(bytes: &[u8; RECORD_SIZE]) -> Slot {
+        if bytes.iter().all(|byte| *byte == ERASED) {
+            return Slot::Empty;
+        }
+
+        let word = |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+
+        let magic = u16::from_le_bytes([bytes[0], bytes[1]]);
+        if magic != RECORD_MAGIC || word(20) != crc32(&bytes[..20]) {
+            return Slot::Corrupted;
+        }
+
+        match IntervalKind::from_byte(bytes[2]) {
+            Some(kind) => Slot::Valid(word(4), SessionRecord {
+                kind,
+                start: word(8),
+                end: word(12),
+                duration: word(16)
+            }),
+            None => Slot::Corrupted
+        }
+    }
+}
+
+/*
+ * Fixed-size ring buffer of SessionRecords.
+ *
+ * Records are appended into erased slots; when the head crosses into a new
+ * sector that sector is erased first, dropping the oldest records.
+ * Every record carries a sequence number and a CRC, so after a reboot
+ * the newest valid record marks the head and half-written slots are skipped.
+ */
+pub struct HistoryLog<S: LogStorage> {
+    storage: S,
+    // Next slot to write
+    head: u32,
+    next_sequence: u32,
+    // Log timeline second that corresponds to Instant 0 of this boot
+    epoch_base: u32,
+    // The timeline is anchored to a real clock, otherwise it only moves while the device is on
+    has_clock: bool,
+    last_end: Option<u32>,
+}
+
+impl<S: LogStorage> HistoryLog<S> {
+    const RECORDS_PER_SECTOR: u32 = S::SECTOR_SIZE / RECORD_SIZE as u32;
+
+    // Scan the storage and continue after the newest valid record
+    pub fn mount(storage: S) -> Result<Self, S::Error> {
+        let mut log = Self::empty(storage);
+        log.scan()?;
+        Ok(log)
+    }
+
+    /*
+     * Mount, or start over on an erased log if the storage can't be read.
+     * If erasing fails too the log stays empty and appends report the error,
+     * the timer runs either way.
+     */
+    pub fn mount_or_format(storage: S) -> Self {
+        let mut log = Self::empty(storage);
+        if let Err(err) = log.scan() {
+            debug_println!("history: mount failed, erasing the log: {:?}", err);
+            for sector in 0..log.storage.sector_count() {
+                if let Err(err) = log.storage.erase_sector(sector) {
+                    debug_println!("history: failed to erase sector {}: {:?}", sector, err);
+                }
+            }
+        }
+        log
+    }
+
+    fn empty(storage: S) -> Self {
+        HistoryLog {
+            storage,
+            head: 0,
+            next_sequence: 0,
+            epoch_base: 0,
+            has_clock: false,
+            last_end: None
+        }
+    }
+
+    // Nothing is changed unless every slot could be read
+    fn scan(&mut self) -> Result<(), S::Error> {
+        let mut newest: Option<(u32, u32, SessionRecord)> = None;
+        let mut corrupted = 0u32;
+        for slot in 0..self.slot_count() {
+            match self.read_slot(slot)? {
+                Slot::Valid(sequence, record) => {
+                    if newest.is_none_or(|(newest_sequence, _, _)| sequence > newest_sequence) {
+                        newest = Some((sequence, slot, record));
+                    }
+                }
+                Slot::Corrupted => corrupted += 1,
+                Slot::Empty => {}
+            }
+        }
+
+        if corrupted > 0 {
+            debug_println!("history: skipped {} corrupted records", corrupted);
+        }
+
+        if let Some((sequence, slot, record)) = newest {
+            self.head = (slot + 1) % self.slot_count();
+            self.next_sequence = sequence.wrapping_add(1);
+            self.last_end = Some(record.end);
+            // No RTC: carry on from where the last boot left off
+            self.epoch_base = record.end;
+        }
+
+        Ok(())
+    }
+
+    pub fn append(&mut self, record: SessionRecord) -> Result<(), HistoryError<S::Error>> {
+        // At worst the rest of a sector is unusable, then the next one gets erased
+        for _ in 0..=Self::RECORDS_PER_SECTOR {
+            let slot = self.head;
+            if slot.is_multiple_of(Self::RECORDS_PER_SECTOR) {
+                self.storage.erase_sector(slot / Self::RECORDS_PER_SECTOR)?;
+            }
+            self.head = (self.head + 1) % self.slot_count();
+
+            // Left behind by a write that was cut short
+            if !matches!(self.read_slot(slot)?, Slot::Empty) {
+                continue;
+            }
+
+            let bytes = record.encode(self.next_sequence);
+            self.storage.write(Self::slot_offset(slot), &bytes)?;
+            self.next_sequence = self.next_sequence.wrapping_add(1);
+            self.last_end = Some(record.end);
+            return Ok(());
+        }
+        Err(HistoryError::NoFreeSlot)
+    }
+
+    // Record a finished interval measured with embassy instants
+    pub fn append_interval(&mut self, kind: IntervalKind, start: Instant, end: Instant) -> Result<(), HistoryError<S::Error>> {
+        let record = SessionRecord::new(kind, self.timestamp(start), self.timestamp(end));
+        self.append(record)
+    }
+
+    // Oldest to newest, corrupted slots are skipped
+    pub fn records(&mut self) -> Records<'_, S> {
+        Records { log: self, index: 0 }
+    }
+
+    pub fn last_end(&self) -> Option<u32> {
+        self.last_end
+    }
+
+    // Seconds on the log's timeline for an instant of this boot
+    pub fn timestamp(&self, at: Instant) -> u32 {
+        self.epoch_base.saturating_add(at.as_secs() as u32)
+    }
+
+    // Anchor the timeline when a real clock is available (e.g. unix time in the simulator)
+    pub fn set_epoch(&mut self, epoch_base: u32) {
+        self.epoch_base = epoch_base;
+        self.has_clock = true;
+    }
+
+    pub fn has_clock(&self) -> bool {
+        self.has_clock
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.slot_count()
+    }
+
+    fn slot_count(&self) -> u32 {
+        self.storage.sector_count() * Self::RECORDS_PER_SECTOR
+    }
+
+    const fn slot_offset(slot: u32) -> u32 {
+        let sector = slot / Self::RECORDS_PER_SECTOR;
+        let index = slot % Self::RECORDS_PER_SECTOR;
+        sector * S::SECTOR_SIZE + index * RECORD_SIZE as u32
+    }
+
+    fn read_slot(&mut self, slot: u32) -> Result<Slot, S::Error> {
+        let mut bytes = [0u8; RECORD_SIZE];
+        self.storage.read(Self::slot_offset(slot), &mut bytes)?;
+        Ok(SessionRecord::decode(&bytes))
+    }
+}
+
+pub struct Records<'a, S: LogStorage> {
+    log: &'a mut HistoryLog<S>,
+    index: u32
+}
+
+impl<S: LogStorage> Iterator for Records<'_, S> {
+    type Item = SessionRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let count = self.log.slot_count();
+        while self.index < count {
+            // The head is the oldest position in the ring
+            let slot = (self.log.head + self.index) % count;
+            self.index += 1;
+            if let Ok(Slot::Valid(_, record)) = self.log.read_slot(slot) {
+                return Some(record);
+            }
+        }
+        None
+    }
+}
+
+/*
+ * Follows session state changes and hands back each
+ * work/break interval once it has ended.
+ */
+#[derive(Debug, Default)]
+pub struct IntervalTracker {
+    current: Option<(IntervalKind, Instant)>
+}
+
+impl IntervalTracker {
+    pub fn transition(&mut self, state: SessionState, now: Instant) -> Option<(IntervalKind, Instant, Instant)> {
+        let next_kind = IntervalKind::from_state(state);
+        if next_kind == self.current.map(|(kind, _)| kind) {
+            return None;
+        }
+
+        let finished = self.current.take().map(|(kind, start)| (kind, start, now));
+        self.current = next_kind.map(|kind| (kind, now));
+        finished
+    }
+
+    // After an undo the running interval continues as the restored kind,
+    // the interval written at the undone switch is left as is
+    pub fn undo(&mut self, state: SessionState) {
+        if let Some((_, start)) = self.current {
+            self.current = IntervalKind::from_state(state).map(|kind| (kind, start));
+        }
+    }
+
+    // Move the start of the running work/break interval along with a manual timer correction
+    pub fn adjust(&mut self, state: SessionState, adjust: TimerAdjust, now: Instant) {
+        let Some((kind, start)) = self.current.as_mut() else {
+            return;
+        };
+        if *kind == IntervalKind::Pause || IntervalKind::from_state(state) != Some(*kind) {
+            return;
+        }
+
+        *start = match adjust {
+            TimerAdjust::Forward(by) => start.checked_sub(by).unwrap_or(Instant::from_ticks(0)),
+            TimerAdjust::Back(by) => (*start + by).min(now),
+        };
+    }
+}
+
+// Bitwise CRC-32 (IEEE), records are small enough that a table isn't worth the flash
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Four records per sector, three sectors
+    const SECTORS: u32 = 3;
+    const SLOTS: u32 = SECTORS * 4;
+
+    // Flash-like storage in RAM, writes can only clear bits
+    struct MemoryStorage {
+        bytes: Vec<u8>,
+        // Reads fail, like a flash that can't be reached
+        unreadable: bool,
+        // Erases report success without clearing anything
+        worn_out: bool,
+    }
+
+    impl MemoryStorage {
+        fn new() -> Self {
+            MemoryStorage {
+                bytes: vec![ERASED; (SECTORS * Self::SECTOR_SIZE) as usize],
+                unreadable: false,
+                worn_out: false,
+            }
+        }
+    }
+
+    impl LogStorage for MemoryStorage {
+        type Error = ();
+
+        const SECTOR_SIZE: u32 = 4 * RECORD_SIZE as u32;
+
+        fn sector_count(&self) -> u32 {
+            SECTORS
+        }
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            if self.unreadable {
+                return Err(());
+            }
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.bytes[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            for (stored, byte) in self.bytes[offset..offset + bytes.len()].iter_mut().zip(bytes) {
+                *stored &= *byte;
+            }
+            Ok(())
+        }
+
+        fn erase_sector(&mut self, sector: u32) -> Result<(), Self::Error> {
+            if self.worn_out {
+                return Ok(());
+            }
+            let start = (sector * Self::SECTOR_SIZE) as usize;
+            self.bytes[start..start + Self::SECTOR_SIZE as usize].fill(ERASED);
+            Ok(())
+        }
+    }
+
+    // Record 'n' runs from 10n to 10n + 5
+    fn record(n: u32) -> SessionRecord {
+        SessionRecord::new(IntervalKind::Work, 10 * n, 10 * n + 5)
+    }
+
+    fn log_with(count: u32) -> HistoryLog<MemoryStorage> {
+        let mut log = HistoryLog::mount(MemoryStorage::new()).unwrap();
+        for n in 0..count {
+            log.append(record(n)).unwrap();
+        }
+        log
+    }
+
+    fn remount(log: HistoryLog<MemoryStorage>) -> HistoryLog<MemoryStorage> {
+        HistoryLog::mount(log.storage).unwrap()
+    }
+
+    fn ends(log: &mut HistoryLog<MemoryStorage>) -> Vec<u32> {
+        log.records().map(|record| record.end).collect()
+    }
+
+    #[test]
+    fn record_round_trips() {
+        let record = SessionRecord::new(IntervalKind::Break, 100, 400);
+        match SessionRecord::decode(&record.encode(7)) {
+            Slot::Valid(sequence, decoded) => assert_eq!((sequence, decoded), (7, record)),
+            _ => panic!("record didn't decode"),
+        }
+    }
+
+    #[test]
+    fn empty_storage_mounts_at_the_start() {
+        let mut log = log_with(0);
+        assert_eq!(log.head, 0);
+        assert_eq!(log.last_end(), None);
+        assert_eq!(log.records().count(), 0);
+    }
+
+    #[test]
+    fn mount_continues_after_the_newest_record() {
+        let mut log = remount(log_with(5));
+        assert_eq!((log.head, log.next_sequence), (5, 5));
+        assert_eq!(log.last_end(), Some(45));
+        assert_eq!(ends(&mut log), [5, 15, 25, 35, 45]);
+    }
+
+    #[test]
+    fn mount_picks_the_highest_sequence_after_a_wrap() {
+        // Two and a half times round, the newest record sits in the middle of the ring
+        let count = 2 * SLOTS + 6;
+        let mut log = remount(log_with(count));
+        assert_eq!(log.next_sequence, count);
+        assert_eq!(log.head, count % SLOTS);
+        assert_eq!(log.last_end(), Some(10 * (count - 1) + 5));
+
+        // The head's sector was erased on the way round, the other two are full
+        let expected: Vec<u32> = (count - 10..count).map(|n| 10 * n + 5).collect();
+        assert_eq!(ends(&mut log), expected);
+
+        // Appending after the remount carries on the same sequence
+        log.append(record(count)).unwrap();
+        let mut log = remount(log);
+        assert_eq!(log.next_sequence, count + 1);
+        assert_eq!(ends(&mut log).last(), Some(&(10 * count + 5)));
+    }
+
+    #[test]
+    fn bad_crc_record_is_skipped() {
+        let mut log = log_with(4);
+        // Clear a bit of the second record's duration, the stored CRC no longer matches
+        let offset = HistoryLog::<MemoryStorage>::slot_offset(1) as usize + 16;
+        log.storage.bytes[offset] &= 0xFE;
+
+        let mut log = remount(log);
+        assert_eq!(ends(&mut log), [5, 25, 35]);
+        assert_eq!(log.next_sequence, 4);
+    }
+
+    #[test]
+    fn corrupted_newest_record_falls_back_to_the_one_before() {
+        let mut log = log_with(3);
+        let offset = HistoryLog::<MemoryStorage>::slot_offset(2) as usize + 20;
+        log.storage.bytes[offset] ^= 0xFF;
+
+        let log = remount(log);
+        assert_eq!(log.next_sequence, 2);
+        assert_eq!(log.last_end(), Some(15));
+    }
+
+    #[test]
+    fn half_written_record_at_the_head_is_left_alone() {
+        let mut log = log_with(3);
+        // Power went out partway through the fourth record
+        let torn = record(3).encode(3);
+        let offset = HistoryLog::<MemoryStorage>::slot_offset(3);
+        log.storage.write(offset, &torn[..RECORD_SIZE / 2]).unwrap();
+
+        let mut log = remount(log);
+        assert_eq!((log.head, log.next_sequence), (3, 3));
+        assert_eq!(ends(&mut log), [5, 15, 25]);
+
+        // The torn slot is stepped over rather than written on top of
+        log.append(record(4)).unwrap();
+        assert_eq!(log.head, 5);
+        let mut log = remount(log);
+        assert_eq!(ends(&mut log), [5, 15, 25, 45]);
+        assert_eq!(log.next_sequence, 4);
+    }
+
+    #[test]
+    fn unreadable_storage_is_formatted() {
+        let mut storage = log_with(5).storage;
+        storage.unreadable = true;
+        assert!(HistoryLog::mount(storage).is_err());
+
+        let mut storage = log_with(5).storage;
+        storage.unreadable = true;
+        let mut log = HistoryLog::mount_or_format(storage);
+        assert_eq!((log.head, log.next_sequence, log.last_end()), (0, 0, None));
+
+        log.storage.unreadable = false;
+        assert_eq!(ends(&mut log), []);
+        log.append(record(9)).unwrap();
+        assert_eq!(ends(&mut remount(log)), [95]);
+    }
+
+    #[test]
+    fn append_reports_a_log_it_could_not_write() {
+        let mut log = log_with(SLOTS);
+        log.storage.worn_out = true;
+        assert!(matches!(log.append(record(SLOTS)), Err(HistoryError::NoFreeSlot)));
+        // Nothing was written over
+        assert_eq!(log.records().count() as u32, SLOTS);
+    }
+}
+
+// ----------- Hardware Backend: ESP32 flash -----------------
+
+#[cfg(not(feature = "simulator"))]
+mod flash_impl {
+    use super::*;
+    use embedded_storage::nor_flash::NorFlash;
+
+    // A window of 'sectors' erase sectors starting at 'base' in a NorFlash
+    pub struct FlashLogStorage<F> {
+        flash: F,
+        base: u32,
+        sectors: u32
+    }
+
+    impl<F: NorFlash> FlashLogStorage<F> {
+        pub const fn new(flash: F, base: u32, sectors: u32) -> Self {
+            FlashLogStorage { flash, base, sectors }
+        }
+    }
+
+    impl<F: NorFlash> LogStorage for FlashLogStorage<F> {
+        type Error = F::Error;
+
+        const SECTOR_SIZE: u32 = F::ERASE_SIZE as u32;
+
+        fn sector_count(&self) -> u32 {
+            self.sectors
+        }
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            self.flash.read(self.base + offset, bytes)
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            self.flash.write(self.base + offset, bytes)
+        }
+
+        fn erase_sector(&mut self, sector: u32) -> Result<(), Self::Error> {
+            let start = self.base + sector * Self::SECTOR_SIZE;
+            self.flash.erase(start, start + Self::SECTOR_SIZE)
+        }
+    }
+}
+
+// ----------- Simulator Backend: plain file -----------------
+
+#[cfg(feature = "simulator")]
+mod file_impl {
+    use super::*;
+    use std::fs::{File, OpenOptions};
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use std::path::Path;
+
+    // Same layout as the flash window, kept in a file next to the simulator
+    pub struct FileLogStorage {
+        file: File,
+        sectors: u32
+    }
+
+    impl FileLogStorage {
+        pub fn open(path: impl AsRef<Path>, sectors: u32) -> std::io::Result<Self> {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(path)?;
+
+            // A new (or short) file starts out erased
+            let len = sectors as u64 * Self::SECTOR_SIZE as u64;
+            let existing = file.metadata()?.len();
+            if existing < len {
+                file.seek(SeekFrom::Start(existing))?;
+                file.write_all(&alloc::vec![ERASED; (len - existing) as usize])?;
+            }
+
+            Ok(FileLogStorage { file, sectors })
+        }
+    }
+
+    impl LogStorage for FileLogStorage {
+        type Error = std::io::Error;
+
+        const SECTOR_SIZE: u32 = 4096;
+
+        fn sector_count(&self) -> u32 {
+            self.sectors
+        }
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            self.file.seek(SeekFrom::Start(offset as u64))?;
+            self.file.read_exact(bytes)
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            self.file.seek(SeekFrom::Start(offset as u64))?;
+            self.file.write_all(bytes)?;
+            self.file.sync_data()
+        }
+
+        fn erase_sector(&mut self, sector: u32) -> Result<(), Self::Error> {
+            self.write(sector * Self::SECTOR_SIZE, &[ERASED; Self::SECTOR_SIZE as usize])
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use core::future::Future;
+use core::sync::atomic::{AtomicBool, Ordering};
+use embassy_futures::join::join3;
+use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, RawMutex};
+use embassy_sync::channel::{Channel, Receiver};
+use embassy_time::Instant;
+use embedded_hal::digital::InputPin;
+use embedded_hal_async::digital::Wait;
+
+use crate::button::{Button, PressDuration};
+use crate::encoder::{AccelerationCurve, Accelerator, RotaryEncoder, RotationDirection};
+use crate::render_display::HoldNotifier;
+use crate::scenes_util::{Turn, UIAction};
+
+#[cfg(not(feature = "simulator"))]
+use esp_hal::gpio::Input;
+
+pub const INPUT_DEPTH: usize = 8;
+
+// Every input device feeds this, scenes and the session state machine read from it
+pub type InputChannel = Channel<CriticalSectionRawMutex, UIAction, INPUT_DEPTH>;
+
+impl From<PressDuration> for UIAction {
+    fn from(press: PressDuration) -> Self {
+        match press {
+            PressDuration::Short => UIAction::Select,
+            PressDuration::Long => UIAction::Back,
+            PressDuration::DoubleClick => UIAction::Undo,
+            PressDuration::MultiTap(_) => UIAction::Home,
+        }
+    }
+}
+
+/*
+ * Source of UIActions for the session state machine.
+ * The hardware reads the channel filled by input_loop, the simulator fills it from the keyboard.
+ */
+pub trait ActionInput {
+    fn next_action(&mut self) -> impl Future<Output = UIAction>;
+}
+
+impl<M: RawMutex, const N: usize> ActionInput for Receiver<'_, M, UIAction, N> {
+    fn next_action(&mut self) -> impl Future<Output = UIAction> {
+        self.receive()
+    }
+}
+
+/*
+ * Set by the session while the screen binds double click and multi-tap.
+ * Otherwise a short press is sent as soon as it's released instead of after the tap window.
+ */
+pub type MultiTapFlag = AtomicBool;
+
+pub struct InputDevices<P, A, B> {
+    pub button: Button<P>,
+    pub encoder: RotaryEncoder<A, B>,
+    // Push switch on the encoder shaft, same gestures as the button
+    pub encoder_switch: Button<P>,
+    pub acceleration: AccelerationCurve,
+}
+
+#[cfg(not(feature = "simulator"))]
+pub type HardwareInputs = InputDevices<Input<'static>, Input<'static>, Input<'static>>;
+
+// Tasks can't be generic, this one runs read_inputs on the board's pins
+#[cfg(not(feature = "simulator"))]
+#[embassy_executor::task]
+pub async fn input_loop(
+    devices: HardwareInputs,
+    channel: &'static InputChannel,
+    hold_notifier: &'static HoldNotifier,
+    multi_tap: &'static MultiTapFlag
+) {
+    read_inputs(devices, channel, hold_notifier, multi_tap).await
+}
+
+// Each device is read on its own, so a turn of the encoder never cuts a button gesture short
+pub async fn read_inputs<P, A, B>(
+    devices: InputDevices<P, A, B>,
+    channel: &InputChannel,
+    hold_notifier: &HoldNotifier,
+    multi_tap: &MultiTapFlag
+) where
+    P: InputPin + Wait,
+    A: InputPin + Wait,
+    B: InputPin + Wait,
+{
+    let InputDevices { mut button, mut encoder, mut encoder_switch, acceleration } = devices;
+    let mut accelerator = Accelerator::new(acceleration);
+    let multi_tap = || multi_tap.load(Ordering::Relaxed);
+
+    let button_actions = async {
+        loop {
+            let press = button.press_with_hold(|hold| hold_notifier.signal(hold), multi_tap).await;
+            channel.send(press.into()).await;
+        }
+    };
+    let rotation_actions = async {
+        loop {
+            // A burst of detents arrives as one count and goes out as one action,
+            // so a fast spin doesn't fill up the channel
+            let detents = encoder.wait_for_steps().await;
+            let accelerated = accelerator.apply(detents, Instant::now());
+            let Some(direction) = RotationDirection::from_steps(detents) else {
+                continue;
+            };
+            channel.send(direction.action(Turn::new(detents, accelerated))).await;
+        }
+    };
+    let switch_actions = async {
+        loop {
+            let press = encoder_switch.press_with_hold(|hold| hold_notifier.signal(hold), multi_tap).await;
+            channel.send(press.into()).await;
+        }
+    };
+
+    join3(button_actions, rotation_actions, switch_actions).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::{block_on, join::join, select::{select, Either}};
+    use embassy_sync::signal::Signal;
+    use embassy_time::Timer;
+
+    use super::*;
+    use crate::mock_pin::MockPin;
+
+    #[test]
+    fn devices_feed_one_channel() {
+        let (button, a, b, switch) = (MockPin::default(), MockPin::default(), MockPin::default(), MockPin::default());
+        let devices = InputDevices {
+            button: Button::new(button.clone()),
+            encoder: RotaryEncoder::new(a.clone(), b.clone()),
+            encoder_switch: Button::new(switch),
+            acceleration: AccelerationCurve::NONE,
+        };
+        let channel = InputChannel::new();
+        let hold_notifier = Signal::new();
+        let multi_tap = MultiTapFlag::new(false);
+
+        let script = async {
+            // One clockwise detent, then a click
+            for (a_high, b_high) in [(true, false), (true, true), (false, true), (false, false)] {
+                a.set(a_high);
+                b.set(b_high);
+                Timer::after_millis(1).await;
+            }
+            button.set(true);
+            Timer::after_millis(100).await;
+            button.set(false);
+        };
+        let actions = async { (channel.receive().await, channel.receive().await) };
+
+        let received = block_on(select(read_inputs(devices, &channel, &hold_notifier, &multi_tap), join(actions, script)));
+        let Either::Second(((turn, press), ())) = received else {
+            unreachable!("read_inputs never returns");
+        };
+        assert_eq!(turn, UIAction::MoveNext(Turn::steps(1)));
+        assert_eq!(press, UIAction::Select);
+    }
+}
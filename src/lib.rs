@@ -4,25 +4,47 @@
 #[cfg(feature = "simulator")]
 extern crate alloc;
 
+// println that goes to the serial monitor on hardware and stdout in the simulator
+macro_rules! debug_println {
+    ($($arg:tt)*) => {{
+        #[cfg(not(feature = "simulator"))]
+        esp_println::println!($($arg)*);
+        #[cfg(feature = "simulator")]
+        std::println!($($arg)*);
+    }};
+}
+
 pub mod tft;
 pub mod payloads;
 pub mod constants;
 pub mod buffer_backend;
 pub mod animations;
+pub mod transitions;
 pub mod scenes_util;
+pub mod scenes;
 pub mod clickable;
+pub mod duration_editor;
+pub mod list_menu;
+pub mod overlay;
+pub mod progress;
 pub mod text_box;
+pub mod glyph_atlas;
 pub mod home_ui;
 pub mod display_driver;
 pub mod color_mixing;
 
-#[cfg(not(feature = "simulator"))]
 pub mod clock;
-#[cfg(not(feature = "simulator"))]
+pub mod clock_source;
+pub mod history;
+pub mod stats;
+pub mod config;
 pub mod time_util;
-#[cfg(not(feature = "simulator"))]
 pub mod render_display;
-#[cfg(not(feature = "simulator"))]
 pub mod button;
-#[cfg(not(feature = "simulator"))]
+pub mod input;
 pub mod encoder;
+#[cfg(test)]
+mod mock_pin;
+// Belongs to build.rs, built here to run its tests
+#[cfg(test)]
+mod scene_compiler;
@@ -0,0 +1,11 @@
+// Sizes the scene files are checked against. build.rs include!s this file as well,
+// so it can only hold plain constants.
+
+pub const DISPLAY_WIDTH: u32 = 320;
+pub const DISPLAY_HEIGHT: u32 = 240;
+
+pub const MAX_ANIMATIONS: usize = 6;
+// Elements a scene can hold
+pub const MAX_SCENE_ELEMENTS: usize = 16;
+// Bytes of text a LabelElement holds
+pub const LABEL_CAPACITY: usize = 24;
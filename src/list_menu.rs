@@ -0,0 +1,182 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, DrawTargetExt, Point, RgbColor, Size},
+    primitives::{Primitive, PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline},
+    Drawable,
+};
+
+use crate::{
+    constants::RGB_DEEP_PURPLE,
+    glyph_atlas::{AtlasTextStyle, ATLAS},
+    scenes_util::{Scene, SceneData, UIAction, UINode},
+    time_util::TimerMode,
+};
+
+const ROW_HEIGHT: u32 = 40;
+const ROW_GAP: u32 = 4;
+const LABEL_SIZE: u32 = 24;
+const LABEL_PADDING: i32 = 12;
+const SCROLLBAR_WIDTH: u32 = 4;
+const ROW_COLOR: Rgb565 = Rgb565::new(5, 10, 5);
+
+// Settings that can be flipped straight from a menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    // Cycle the timer display through seconds, tenths and hundredths
+    Resolution,
+}
+
+// What picking a menu item does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuAction {
+    // Start a work session in this mode
+    StartMode(TimerMode),
+    OpenScene(Scene),
+    ToggleSetting(Setting),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MenuItem {
+    pub label: &'static str,
+    pub action: MenuAction,
+}
+
+impl MenuItem {
+    pub const fn new(label: &'static str, action: MenuAction) -> Self {
+        MenuItem { label, action }
+    }
+}
+
+/*
+ * Vertical list of items, one of them selected.
+ * The list scrolls once there are more items than rows fit in 'position'.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct ListMenu {
+    pub position: Rectangle,
+    items: &'static [MenuItem],
+    selected: u8,
+    // First item shown at the top of the list
+    scroll: u8,
+}
+
+impl ListMenu {
+    pub const fn new(position: Rectangle, items: &'static [MenuItem]) -> Self {
+        ListMenu { position, items, selected: 0, scroll: 0 }
+    }
+
+    pub fn selected(&self) -> Option<&MenuItem> {
+        self.items.get(self.selected as usize)
+    }
+
+    fn visible_rows(&self) -> u8 {
+        ((self.position.size.height + ROW_GAP) / (ROW_HEIGHT + ROW_GAP)).clamp(1, u8::MAX as u32) as u8
+    }
+
+    fn scrollable(&self) -> bool {
+        self.items.len() > self.visible_rows() as usize
+    }
+
+    // Rectangle of the item 'row' places below the top of the list
+    fn row_rect(&self, row: u8) -> Rectangle {
+        let width = if self.scrollable() {
+            self.position.size.width - SCROLLBAR_WIDTH - ROW_GAP
+        } else {
+            self.position.size.width
+        };
+        Rectangle::new(
+            self.position.top_left + Point::new(0, (row as u32 * (ROW_HEIGHT + ROW_GAP)) as i32),
+            Size::new(width, ROW_HEIGHT)
+        )
+    }
+
+    /*
+     * Moves the selection and scrolls to keep it in view,
+     * returns the item's action on Select. The session and the renderer
+     * both step their own copy of the menu, so they agree on the selection.
+     */
+    pub fn step(&mut self, action: UIAction) -> Option<MenuAction> {
+        let last = self.items.len().saturating_sub(1) as u8;
+        match action {
+            // One row per detent, acceleration would skip past items
+            UIAction::MoveNext(turn) => self.selected = self.selected.saturating_add(turn.detents).min(last),
+            UIAction::MoveBack(turn) => self.selected = self.selected.saturating_sub(turn.detents),
+            UIAction::Select => return self.selected().map(|item| item.action),
+            UIAction::Back | UIAction::Undo | UIAction::Home => return None,
+        }
+
+        let rows = self.visible_rows();
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+        None
+    }
+
+    fn draw_scrollbar<D: DrawTarget<Color = Rgb565>>(&self, target: &mut D) -> Result<(), D::Error> {
+        let track = Rectangle::new(
+            self.position.top_left + Point::new((self.position.size.width - SCROLLBAR_WIDTH) as i32, 0),
+            Size::new(SCROLLBAR_WIDTH, self.position.size.height)
+        );
+        track.into_styled(PrimitiveStyle::with_fill(ROW_COLOR)).draw(target)?;
+
+        let count = self.items.len() as u32;
+        let thumb_height = (track.size.height * self.visible_rows() as u32 / count).max(SCROLLBAR_WIDTH);
+        let thumb_y = track.size.height * self.scroll as u32 / count;
+        Rectangle::new(
+            track.top_left + Point::new(0, thumb_y as i32),
+            Size::new(SCROLLBAR_WIDTH, thumb_height)
+        )
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::WHITE))
+        .draw(target)
+    }
+}
+
+impl UINode for ListMenu {
+    fn get_position(&self) -> &Rectangle {
+        &self.position
+    }
+
+    // The highlight sits on the selected row, not around the whole list
+    fn focus_rect(&self) -> Rectangle {
+        self.row_rect(self.selected - self.scroll)
+    }
+
+    fn handle_action(&mut self, _scene: &mut SceneData, action: UIAction) {
+        // Picking an item is up to the session, here only the selection moves
+        self.step(action);
+    }
+}
+
+impl Drawable for ListMenu {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        let font = ATLAS.font(LABEL_SIZE);
+        let shown = self.items.iter().enumerate().skip(self.scroll as usize).take(self.visible_rows() as usize);
+
+        for (row, (index, item)) in shown.enumerate() {
+            let rect = self.row_rect(row as u8);
+            let fill = if index == self.selected as usize { RGB_DEEP_PURPLE } else { ROW_COLOR };
+            rect.into_styled(PrimitiveStyle::with_fill(fill)).draw(target)?;
+
+            if let Some(font) = font {
+                let style = AtlasTextStyle::new(font, Rgb565::WHITE)
+                    .with_alignment(Alignment::Left)
+                    .with_baseline(Baseline::Middle);
+                let anchor = Point::new(rect.top_left.x + LABEL_PADDING, rect.center().y);
+                style.draw_over(item.label, anchor, fill, &mut target.clipped(&rect))?;
+            }
+        }
+
+        if self.scrollable() {
+            self.draw_scrollbar(target)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use core::{cell::Cell, convert::Infallible};
+use std::rc::Rc;
+
+use embassy_time::{Duration, Timer};
+use embedded_hal::digital::{ErrorType, InputPin};
+use embedded_hal_async::digital::Wait;
+
+// How often a waiting MockPin looks at its level again
+const POLL: Duration = Duration::from_micros(200);
+
+/*
+ * Pin for host tests, its level is set by the test through a clone.
+ * Waiting polls the level, edges shorter than POLL can be missed.
+ */
+#[derive(Clone, Default)]
+pub struct MockPin(Rc<Cell<bool>>);
+
+impl MockPin {
+    pub fn set(&self, high: bool) {
+        self.0.set(high);
+    }
+
+    async fn wait_for_level(&self, high: bool) {
+        while self.0.get() != high {
+            Timer::after(POLL).await;
+        }
+    }
+}
+
+impl ErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl InputPin for MockPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.0.get())
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.0.get())
+    }
+}
+
+impl Wait for MockPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await;
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await;
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await;
+        self.wait_for_level(true).await;
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await;
+        self.wait_for_level(false).await;
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        let level = self.0.get();
+        self.wait_for_level(!level).await;
+        Ok(())
+    }
+}
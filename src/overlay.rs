@@ -0,0 +1,191 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, DrawTargetExt, Point, RgbColor, Size},
+    primitives::{Primitive, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, StrokeAlignment},
+    text::{Alignment, Baseline},
+    Drawable,
+};
+
+use crate::{
+    constants::{FRAME_RATE, RGB_DEEP_PURPLE},
+    glyph_atlas::{AtlasTextStyle, ATLAS},
+    scenes_util::UIAction,
+};
+
+pub const DIALOG_AREA: Rectangle = Rectangle::new(Point::new(30, 40), Size::new(260, 160));
+pub const TOAST_AREA: Rectangle = Rectangle::new(Point::new(60, 196), Size::new(200, 32));
+// Frames a toast stays up for, about two seconds
+pub const TOAST_FRAMES: u8 = 2 * FRAME_RATE as u8;
+
+const PANEL_COLOR: Rgb565 = Rgb565::new(5, 10, 5);
+const BUTTON_COLOR: Rgb565 = Rgb565::new(8, 16, 8);
+const BORDER_WIDTH: u32 = 2;
+const PADDING: i32 = 12;
+const BUTTON_SIZE: Size = Size::new(96, 36);
+// Title line, the message starts below it
+const TITLE_LINE: i32 = 32;
+
+/*
+ * Yes/No question drawn over the scene on screen.
+ * The session and the renderer both step their own copy, same as a ListMenu.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Dialog {
+    pub title: &'static str,
+    pub message: &'static str,
+    // No is picked until the user turns to Yes, so a stray press doesn't confirm
+    confirm: bool,
+}
+
+impl Dialog {
+    pub const fn new(title: &'static str, message: &'static str) -> Self {
+        Dialog { title, message, confirm: false }
+    }
+
+    /*
+     * Turning moves between the buttons, Select answers with the one picked.
+     * Back and Home always answer No.
+     */
+    pub fn step(&mut self, action: UIAction) -> Option<bool> {
+        match action {
+            UIAction::MoveBack(_) => self.confirm = true,
+            UIAction::MoveNext(_) => self.confirm = false,
+            UIAction::Select => return Some(self.confirm),
+            UIAction::Back | UIAction::Home => return Some(false),
+            UIAction::Undo => {}
+        }
+        None
+    }
+
+    // Yes on the left, No on the right
+    fn button_rect(confirm: bool) -> Rectangle {
+        let y = DIALOG_AREA.top_left.y + DIALOG_AREA.size.height as i32 - PADDING - BUTTON_SIZE.height as i32;
+        let x = if confirm {
+            DIALOG_AREA.top_left.x + PADDING
+        } else {
+            DIALOG_AREA.top_left.x + DIALOG_AREA.size.width as i32 - PADDING - BUTTON_SIZE.width as i32
+        };
+        Rectangle::new(Point::new(x, y), BUTTON_SIZE)
+    }
+
+    fn draw_button<D: DrawTarget<Color = Rgb565>>(&self, confirm: bool, target: &mut D) -> Result<(), D::Error> {
+        let rect = Self::button_rect(confirm);
+        let fill = if confirm == self.confirm { RGB_DEEP_PURPLE } else { BUTTON_COLOR };
+        rect.into_styled(PrimitiveStyle::with_fill(fill)).draw(target)?;
+
+        if let Some(font) = ATLAS.font(16) {
+            let style = AtlasTextStyle::new(font, Rgb565::WHITE)
+                .with_alignment(Alignment::Center)
+                .with_baseline(Baseline::Middle);
+            let label = if confirm { "Yes" } else { "No" };
+            style.draw_over(label, rect.center(), fill, &mut target.clipped(&rect))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drawable for Dialog {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        let panel = PrimitiveStyleBuilder::new()
+            .fill_color(PANEL_COLOR)
+            .stroke_color(Rgb565::WHITE)
+            .stroke_width(BORDER_WIDTH)
+            .stroke_alignment(StrokeAlignment::Inside)
+            .build();
+        DIALOG_AREA.into_styled(panel).draw(target)?;
+
+        let mut text = target.clipped(&DIALOG_AREA);
+        let left = DIALOG_AREA.top_left.x + PADDING;
+        if let Some(font) = ATLAS.font(24) {
+            let style = AtlasTextStyle::new(font, Rgb565::WHITE);
+            style.draw_over(self.title, Point::new(left, DIALOG_AREA.top_left.y + PADDING), PANEL_COLOR, &mut text)?;
+        }
+        if let Some(font) = ATLAS.font(16) {
+            let style = AtlasTextStyle::new(font, Rgb565::WHITE);
+            style.draw_over(self.message, Point::new(left, DIALOG_AREA.top_left.y + PADDING + TITLE_LINE), PANEL_COLOR, &mut text)?;
+        }
+
+        self.draw_button(true, target)?;
+        self.draw_button(false, target)
+    }
+}
+
+// Short line of text along the bottom of the screen, goes away on its own
+#[derive(Debug, Clone, Copy)]
+pub struct Toast {
+    pub text: &'static str,
+    frames_left: u8,
+}
+
+impl Toast {
+    pub const fn new(text: &'static str) -> Self {
+        Toast { text, frames_left: TOAST_FRAMES }
+    }
+
+    // Counts down one frame, false once the toast should be taken down
+    pub fn tick(&mut self) -> bool {
+        self.frames_left = self.frames_left.saturating_sub(1);
+        self.frames_left > 0
+    }
+}
+
+impl Drawable for Toast {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        TOAST_AREA.into_styled(PrimitiveStyle::with_fill(RGB_DEEP_PURPLE)).draw(target)?;
+
+        if let Some(font) = ATLAS.font(16) {
+            let style = AtlasTextStyle::new(font, Rgb565::WHITE)
+                .with_alignment(Alignment::Center)
+                .with_baseline(Baseline::Middle);
+            style.draw_over(self.text, TOAST_AREA.center(), RGB_DEEP_PURPLE, &mut target.clipped(&TOAST_AREA))?;
+        }
+        Ok(())
+    }
+}
+
+/*
+ * Everything drawn on top of the current scene.
+ * Overlays live in the frame buffer like the scene does; taking one down
+ * redraws the scene under its area only.
+ */
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Overlays {
+    pub dialog: Option<Dialog>,
+    pub toast: Option<Toast>,
+}
+
+impl Overlays {
+    pub fn covers(&self, area: &Rectangle) -> bool {
+        let dialog = self.dialog.is_some() && !DIALOG_AREA.intersection(area).is_zero_sized();
+        let toast = self.toast.is_some() && !TOAST_AREA.intersection(area).is_zero_sized();
+        dialog || toast
+    }
+}
+
+impl Drawable for Overlays {
+    type Color = Rgb565;
+    type Output = ();
+
+    // The dialog is modal, it goes over the toast
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        if let Some(toast) = &self.toast {
+            toast.draw(target)?;
+        }
+        if let Some(dialog) = &self.dialog {
+            dialog.draw(target)?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{animations::Animation, scenes_util::SceneData};
+use crate::{animations::Animation, overlay::{Dialog, Toast}, progress::PROGRESS_MAX, scenes_util::{ElementId, SceneData, UIAction, UIType}, stats::StatsSummary};
 
 #[derive(Debug, PartialEq, Default, Clone, Copy)]
 pub enum SessionState {
@@ -6,20 +6,74 @@ pub enum SessionState {
     MainMenu,
     Working,
     Break,
-    Paused
+    Paused,
+    Statistics,
+    Settings,
+    // Block lengths for the Taro modes, picked before the session starts
+    ConfigTaro,
+    ConfigTaroPlus
+}
+
+// Position within a set of pomodoro work blocks, e.g. 3/4
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Cycle {
+    pub current: u8,
+    pub total: u8
+}
+
+// How far into the current countdown block the session is, in whole seconds
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BlockProgress {
+    pub elapsed: u32,
+    pub target: u32
+}
+
+impl BlockProgress {
+    // In thousandths, what progress widgets are driven with
+    pub fn permille(&self) -> u16 {
+        if self.target == 0 {
+            return PROGRESS_MAX;
+        }
+        (self.elapsed as u64 * PROGRESS_MAX as u64 / self.target as u64).min(PROGRESS_MAX as u64) as u16
+    }
+}
+
+// One update of the session timers
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TimeUpdate {
+    pub digits: [u8; 20],
+    pub state: SessionState,
+    pub cycle: Option<Cycle>,
+    // Net manual adjustment in minutes
+    pub adjusted_mins: i16,
+    // Progress through the current countdown block
+    pub progress: Option<BlockProgress>
 }
 
 pub struct Packet(pub Payload);
 
 #[derive(Debug, Clone, Copy)]
 pub enum Payload {
-    Time([u8; 20], SessionState),
+    Time(TimeUpdate),
     Animate(Animation),
-    NewScene(SceneData),
+    NewScene(&'static SceneData),
+    // New version of one element on the current scene
+    Element(ElementId, UIType),
+    Stats(StatsSummary),
     Menu,
     Empty
 }
 
+// Drawn over whatever scene is on screen, sent apart from Packets so a timer update can't replace one
+#[derive(Debug, Clone, Copy)]
+pub enum OverlayEvent {
+    Dialog(Dialog),
+    // Input for the open dialog, or the focused element of the scene when there isn't one
+    Action(UIAction),
+    CloseDialog,
+    Toast(Toast)
+}
+
 impl Default for Packet {
     fn default() -> Self {
         Packet(Payload::Menu)
@@ -27,9 +81,8 @@ impl Default for Packet {
 }
 
 impl Packet {
-    pub fn from_time(time: [u8; 20], timer: SessionState) -> Self {
-        let payload = Payload::Time(time, timer);
-        Packet(payload)
+    pub fn from_time(update: TimeUpdate) -> Self {
+        Packet(Payload::Time(update))
     }
 
     pub fn menu() -> Self {
@@ -0,0 +1,274 @@
+use core::f32::consts::TAU;
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{Dimensions, DrawTarget, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+    Drawable, Pixel,
+};
+use micromath::F32;
+
+use crate::{buffer_backend::BufferData, color_mixing::alpha_blending::RGBa};
+
+// Progress is kept in thousandths of the block
+pub const PROGRESS_MAX: u16 = 1000;
+const TRACK_COLOR: Rgb565 = Rgb565::new(8, 16, 8);
+
+// Alpha for a 0.0-1.0 coverage
+fn alpha(coverage: f32) -> u8 {
+    (coverage.clamp(0.0, 1.0) * 255.0) as u8
+}
+
+/*
+ * Ring that fills clockwise from 12 o'clock.
+ * Edges are anti-aliased against 'background', so every pixel of the ring
+ * can be rewritten on its own without reading the frame buffer back.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressRing {
+    pub center: Point,
+    // Outer edge, the ring grows inwards by 'thickness'
+    pub radius: u32,
+    pub thickness: u32,
+    pub color: Rgb565,
+    pub track_color: Rgb565,
+    pub background: Rgb565,
+    progress: u16,
+}
+
+impl ProgressRing {
+    pub const fn new(center: Point, radius: u32, color: Rgb565) -> Self {
+        ProgressRing {
+            center,
+            radius,
+            thickness: 8,
+            color,
+            track_color: TRACK_COLOR,
+            background: Rgb565::BLACK,
+            progress: 0,
+        }
+    }
+
+    pub const fn with_thickness(self, thickness: u32) -> Self {
+        ProgressRing { thickness, ..self }
+    }
+
+    pub const fn with_track_color(self, track_color: Rgb565) -> Self {
+        ProgressRing { track_color, ..self }
+    }
+
+    pub const fn with_background(self, background: Rgb565) -> Self {
+        ProgressRing { background, ..self }
+    }
+
+    pub const fn with_progress(self, progress: u16) -> Self {
+        let progress = if progress > PROGRESS_MAX { PROGRESS_MAX } else { progress };
+        ProgressRing { progress, ..self }
+    }
+
+    pub const fn area(&self) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.center.x - self.radius as i32, self.center.y - self.radius as i32),
+            Size::new(2 * self.radius + 1, 2 * self.radius + 1)
+        )
+    }
+
+    fn inner_radius(&self) -> f32 {
+        self.radius.saturating_sub(self.thickness) as f32
+    }
+
+    // Angular anti-aliasing is worked out halfway through the ring
+    fn middle_radius(&self) -> f32 {
+        (self.radius as f32 + self.inner_radius()) / 2.0
+    }
+
+    fn end_angle(progress: u16) -> f32 {
+        progress as f32 * TAU / PROGRESS_MAX as f32
+    }
+
+    // Clockwise from 12 o'clock, 0 to TAU
+    fn angle(offset: Point) -> f32 {
+        let angle = F32(offset.x as f32).atan2(F32(-offset.y as f32)).0;
+        if angle < 0.0 { angle + TAU } else { angle }
+    }
+
+    // Colour of the ring at 'offset' from the centre and how much of the pixel it covers
+    fn paint(&self, offset: Point) -> Option<(Rgb565, u8)> {
+        let (outer, inner) = (self.radius as f32, self.inner_radius());
+        let distance_squared = (offset.x * offset.x + offset.y * offset.y) as f32;
+
+        // (r² - d²) / 2r is the distance to an edge, near enough to it
+        let inside_outer = (outer * outer - distance_squared) / (2.0 * outer) + 0.5;
+        let outside_inner = if inner > 0.0 { (distance_squared - inner * inner) / (2.0 * inner) + 0.5 } else { 1.0 };
+        let coverage = inside_outer.min(outside_inner);
+        if coverage <= 0.0 {
+            return None;
+        }
+
+        let filled = match self.progress {
+            0 => 0.0,
+            PROGRESS_MAX => 1.0,
+            progress => (Self::end_angle(progress) - Self::angle(offset)) * self.middle_radius() + 0.5,
+        };
+        let color = RGBa::new(self.color, alpha(filled)).blend(self.track_color);
+        Some((color, alpha(coverage)))
+    }
+
+    // Colour of the pixel 'offset' from the centre, None if the ring doesn't touch it
+    fn shade(&self, offset: Point) -> Option<Rgb565> {
+        self.paint(offset).map(|(color, coverage)| RGBa::new(color, coverage).blend(self.background))
+    }
+
+    // Bounding box of the part of the ring between two angles
+    fn segment_area(&self, start: f32, end: f32) -> Rectangle {
+        let (outer, inner) = (self.radius as f32, self.inner_radius());
+        let edge = |angle: f32, radius: f32| {
+            let (sin, cos) = F32(angle).sin_cos();
+            (sin.0 * radius, -cos.0 * radius)
+        };
+
+        let mut min = edge(start, outer);
+        let mut max = min;
+        let mut include = |(x, y): (f32, f32)| {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        };
+        include(edge(start, inner));
+        include(edge(end, outer));
+        include(edge(end, inner));
+        // 12, 3, 6 and 9 o'clock stick out past the ends of the segment
+        let quarter = TAU / 4.0;
+        let mut angle = F32(start / quarter).ceil().0 * quarter;
+        while angle <= end {
+            include(edge(angle, outer));
+            angle += quarter;
+        }
+
+        // A pixel either side for the anti-aliased edges
+        let top_left = self.center + Point::new(F32(min.0).floor().0 as i32 - 1, F32(min.1).floor().0 as i32 - 1);
+        let bottom_right = self.center + Point::new(F32(max.0).ceil().0 as i32 + 1, F32(max.1).ceil().0 as i32 + 1);
+        Rectangle::with_corners(top_left, bottom_right).intersection(&self.area())
+    }
+
+    /*
+     * Moves the ring to 'progress' straight in the frame buffer,
+     * only the arc between the old and new end is redrawn and marked dirty.
+     */
+    pub fn advance(&mut self, progress: u16, buffer: &mut BufferData) {
+        let progress = progress.min(PROGRESS_MAX);
+        if progress == self.progress {
+            return;
+        }
+        let (from, to) = (self.progress.min(progress), self.progress.max(progress));
+        self.progress = progress;
+
+        let margin = 1.0 / self.middle_radius().max(1.0);
+        let (start, end) = (Self::end_angle(from) - margin, Self::end_angle(to) + margin);
+        let area = self.segment_area(start, end).intersection(&buffer.bounding_box());
+        if area.is_zero_sized() {
+            return;
+        }
+
+        let pixels = area.points().map(|point| {
+            let offset = point - self.center;
+            let angle = Self::angle(offset);
+            // The segment can reach past 12 o'clock on either side
+            let in_segment = (start..=end).contains(&angle) || angle + TAU <= end || angle - TAU >= start;
+            match self.shade(offset) {
+                Some(color) if in_segment => (color, 255),
+                _ => (self.background, 0),
+            }
+        });
+        buffer.blend_iter(area.top_left, area.size.width, area.size.height, pixels);
+        buffer.mark_dirty(area);
+    }
+
+    // The whole ring blended over what's in the frame buffer already, for a ring that isn't on a solid colour
+    pub fn blend_over(&self, buffer: &mut BufferData) {
+        let area = self.area().intersection(&buffer.bounding_box());
+        let pixels = area.points().map(|point| self.paint(point - self.center).unwrap_or((self.background, 0)));
+        buffer.blend_iter(area.top_left, area.size.width, area.size.height, pixels);
+        buffer.mark_dirty(area);
+    }
+}
+
+impl Drawable for ProgressRing {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        let pixels = self.area().points().filter_map(|point| {
+            self.shade(point - self.center).map(|color| Pixel(point, color))
+        });
+        target.draw_iter(pixels)
+    }
+}
+
+// Horizontal bar that fills left to right, the end of the fill is anti-aliased
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressBar {
+    pub position: Rectangle,
+    pub color: Rgb565,
+    pub track_color: Rgb565,
+    progress: u16,
+}
+
+impl ProgressBar {
+    pub const fn new(position: Rectangle, color: Rgb565) -> Self {
+        ProgressBar { position, color, track_color: TRACK_COLOR, progress: 0 }
+    }
+
+    pub const fn with_track_color(self, track_color: Rgb565) -> Self {
+        ProgressBar { track_color, ..self }
+    }
+
+    // Filled width in pixels, fractional
+    fn fill(&self, progress: u16) -> f32 {
+        self.position.size.width as f32 * progress as f32 / PROGRESS_MAX as f32
+    }
+
+    fn shade(&self, column: u32) -> Rgb565 {
+        let covered = self.fill(self.progress) - column as f32;
+        RGBa::new(self.color, alpha(covered)).blend(self.track_color)
+    }
+
+    // Moves the bar to 'progress', only the columns between the old and new end are redrawn and marked dirty
+    pub fn advance(&mut self, progress: u16, buffer: &mut BufferData) {
+        let progress = progress.min(PROGRESS_MAX);
+        if progress == self.progress {
+            return;
+        }
+        let (from, to) = (self.fill(self.progress.min(progress)), self.fill(self.progress.max(progress)));
+        self.progress = progress;
+
+        let first = from as u32;
+        let last = (F32(to).ceil().0 as u32).min(self.position.size.width);
+        let area = Rectangle::new(
+            self.position.top_left + Point::new(first as i32, 0),
+            Size::new(last.saturating_sub(first), self.position.size.height)
+        ).intersection(&buffer.bounding_box());
+        if area.is_zero_sized() {
+            return;
+        }
+
+        let pixels = area.points().map(|point| (self.shade((point.x - self.position.top_left.x) as u32), 255));
+        buffer.blend_iter(area.top_left, area.size.width, area.size.height, pixels);
+        buffer.mark_dirty(area);
+    }
+}
+
+impl Drawable for ProgressBar {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        let pixels = self.position.points().map(|point| {
+            Pixel(point, self.shade((point.x - self.position.top_left.x) as u32))
+        });
+        target.draw_iter(pixels)
+    }
+}
@@ -1,13 +1,25 @@
-use embassy_executor::{SpawnError, Spawner};
-use embassy_futures::select::{select, Either};
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
-use embassy_time::{Duration, Ticker};
+use embassy_futures::select::{select3, select4, Either3, Either4};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel, signal::Signal};
+use embassy_time::Ticker;
 
-use crate::tft::HardwareTFT;
-use crate::payloads::Packet;
-use crate::constants::FRAME_RATE;
+use crate::button::HoldEvent;
+use crate::display_driver::DisplayDriver;
+use crate::tft::TFT;
+use crate::payloads::{OverlayEvent, Packet};
+
+#[cfg(not(feature = "simulator"))]
+use {
+    embassy_executor::{SpawnError, Spawner},
+    embassy_time::Duration,
+    crate::tft::HardwareTFT,
+    crate::constants::FRAME_RATE,
+};
 
 pub type TFTNotifier = Signal<CriticalSectionRawMutex, Packet>;
+// Separate from TFTNotifier so button feedback never replaces a pending timer packet
+pub type HoldNotifier = Signal<CriticalSectionRawMutex, HoldEvent>;
+// Queued rather than signalled, every step of a dialog or menu has to reach the renderer
+pub type OverlayNotifier = Channel<CriticalSectionRawMutex, OverlayEvent, 4>;
 pub struct TFTRender<'a>(&'a TFTNotifier);
 
 impl TFTRender<'_> {
@@ -16,34 +28,43 @@ impl TFTRender<'_> {
         Signal::new()
     }
 
+    #[cfg(not(feature = "simulator"))]
     pub fn new(
         tft: HardwareTFT,
         notifier: &'static TFTNotifier,
+        hold_notifier: &'static HoldNotifier,
+        overlay_notifier: &'static OverlayNotifier,
         spawner: Spawner
         ) -> Result<Self, SpawnError> {
-        spawner.spawn(render_loop(tft, notifier))?;
+        spawner.spawn(render_loop(tft, notifier, hold_notifier, overlay_notifier))?;
         Ok(Self(notifier))
     }
 
     // called by Session
     pub fn render(&self, frame: Packet) {
-       self.0.signal(frame); 
+       self.0.signal(frame);
     }
 }
 
+#[cfg(not(feature = "simulator"))]
 #[embassy_executor::task]
 async fn render_loop(
     tft: HardwareTFT,
-    notifier: &'static TFTNotifier
+    notifier: &'static TFTNotifier,
+    hold_notifier: &'static HoldNotifier,
+    overlay_notifier: &'static OverlayNotifier
 ) -> ! {
     // safely start state loop
-    let _err = inner_render_loop(tft, notifier).await;
+    let _err = inner_render_loop(tft, notifier, hold_notifier, overlay_notifier).await;
 }
 
 // final step; draws to the display
+#[cfg(not(feature = "simulator"))]
 async fn inner_render_loop(
     mut tft: HardwareTFT,
-    notifier: &'static TFTNotifier
+    notifier: &'static TFTNotifier,
+    hold_notifier: &'static HoldNotifier,
+    overlay_notifier: &'static OverlayNotifier
 ) -> ! {
     let packet = Packet::default();
     tft.handle_payload(&packet);
@@ -51,25 +72,48 @@ async fn inner_render_loop(
     let mut frame_ticker = Ticker::every(Duration::from_hz(FRAME_RATE));
 
     loop {
-        // Hybrid Rendering System
-        // 30 FPS while playing animations
-        // Event-driven renders for state changes
+        render_step(&mut tft, notifier, hold_notifier, overlay_notifier, &mut frame_ticker).await;
+    }
+}
+
+// One pass of the render loop, shared by the hardware task and the simulator
+pub async fn render_step<D: DisplayDriver>(
+    tft: &mut TFT<D>,
+    notifier: &TFTNotifier,
+    hold_notifier: &HoldNotifier,
+    overlay_notifier: &OverlayNotifier,
+    frame_ticker: &mut Ticker
+)
+where
+    D::Error: core::fmt::Debug,
+{
+    // Hybrid Rendering System
+    // 30 FPS while playing animations
+    // Event-driven renders for state changes
 
-        // handle any incoming event payloads first [high priority] 
+    // handle any incoming event payloads first [high priority]
 
-        if !tft.playing_animation {
-            let notification = notifier.wait().await;
-            tft.handle_payload(&notification);
-        } else {
-            match select(frame_ticker.next(), notifier.wait()).await {
-                Either::First(_) => {
-                    tft.render_next_frame();
-                }
-                // if a new payload was recieved before the sleep, 
-                // start loop with new payload
-                Either::Second(notification) => {
-                    tft.handle_payload(&notification);
-                }
+    if !tft.playing_animation {
+        match select3(notifier.wait(), hold_notifier.wait(), overlay_notifier.receive()).await {
+            Either3::First(notification) => tft.handle_payload(&notification),
+            Either3::Second(hold) => tft.render_hold(hold),
+            Either3::Third(overlay) => tft.handle_overlay(overlay),
+        }
+    } else {
+        match select4(frame_ticker.next(), notifier.wait(), hold_notifier.wait(), overlay_notifier.receive()).await {
+            Either4::First(_) => {
+                tft.render_next_frame();
+            }
+            // if a new payload was recieved before the sleep,
+            // start loop with new payload
+            Either4::Second(notification) => {
+                tft.handle_payload(&notification);
+            }
+            Either4::Third(hold) => {
+                tft.render_hold(hold);
+            }
+            Either4::Fourth(overlay) => {
+                tft.handle_overlay(overlay);
             }
         }
     }
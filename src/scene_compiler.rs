@@ -0,0 +1,652 @@
+/*
+ * Scene file compiler, run by build.rs to turn src/assets/scenes into SceneData consts.
+ * build.rs pulls this file in with #[path]; the crate builds it for its tests only,
+ * so nothing in here can use the crate.
+ */
+use std::{collections::BTreeMap, env, fmt, fs, path::{Path, PathBuf}};
+
+// The same limits the crate is built with
+mod limits {
+    include!("limits.rs");
+}
+use limits::{DISPLAY_HEIGHT, DISPLAY_WIDTH, LABEL_CAPACITY, MAX_ANIMATIONS, MAX_SCENE_ELEMENTS};
+
+const ASSETS_DIR: &str = "src/assets";
+
+pub struct SceneError {
+    path: PathBuf,
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.path.display(), self.line, self.message)
+    }
+}
+
+// Asset bytes and animation metadata shared by every scene, each included once
+#[derive(Default)]
+pub struct SceneAssets {
+    files: BTreeMap<String, String>,
+    animations: BTreeMap<(String, u32, u32, u32), String>,
+    // Consts for the assets, goes in front of the scenes
+    pub code: String,
+}
+
+impl SceneAssets {
+    // Const holding the file's bytes, checked to hold at least 'min_len' of them
+    fn file(&mut self, name: &str, min_len: usize) -> Result<String, String> {
+        let path = Path::new(ASSETS_DIR).join(name);
+        let len = fs::metadata(&path).map_err(|_| format!("asset {} not found in {}", name, ASSETS_DIR))?.len() as usize;
+        if len < min_len {
+            return Err(format!("asset {} is {} bytes, the given size needs {}", name, len, min_len));
+        }
+        if let Some(ident) = self.files.get(name) {
+            return Ok(ident.clone());
+        }
+
+        println!("cargo:rerun-if-changed={}", path.display());
+        let ident = format!("ASSET_{}", name.to_uppercase().replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+        let absolute = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(&path);
+        self.code.push_str(&format!("const {}: &[u8] = include_bytes!({:?});\n", ident, absolute.display().to_string()));
+        self.files.insert(name.to_string(), ident.clone());
+        Ok(ident)
+    }
+
+    fn animation(&mut self, name: &str, (width, height): (u32, u32), frames: u32) -> Result<String, String> {
+        let data = self.file(name, (width * height * 2 * frames) as usize)?;
+        let key = (name.to_string(), width, height, frames);
+        if let Some(ident) = self.animations.get(&key) {
+            return Ok(ident.clone());
+        }
+
+        let ident = format!("ANIMATION_{}", self.animations.len());
+        self.code.push_str(&format!(
+            "const {}: AnimationMetadata = AnimationMetadata::new({}, {}, {}, {});\n",
+            ident, data, width, height, frames
+        ));
+        self.animations.insert(key, ident.clone());
+        Ok(ident)
+    }
+}
+
+// Code for an element, focusable ones get their next/prev element filled in once the focus line is known
+enum ElementCode {
+    Fixed(String),
+    Linked(Box<dyn Fn(usize, usize) -> String>),
+    // Holds focus but moves it on its own
+    Focusable(String),
+}
+
+struct SceneElement {
+    line: usize,
+    name: Option<String>,
+    id: Option<String>,
+    code: ElementCode,
+}
+
+/*
+ * One directive per line, '#' starts a comment. Values are key=value,
+ * quoted if they have spaces: text="Work --:--".
+ *  scene <Scene variant>                       must come first
+ *  transition cut|slide|fade|wipe              how the scene comes on screen, cut if not given
+ *  image file=<asset> at=x,y size=WxH          raw RGB565
+ *  animation file=<asset> at=x,y size=WxH frames=N [loop]
+ *  label at=x,y text=".." [font=px] [color=C]
+ *  text at=x,y size=WxH text=".." [font=px] [color=C] [align=left|center|right]
+ *  chart at=x,y size=WxH color=C highlight=C
+ *  ring at=x,y radius=N color=C [thickness=N] [track=C] [background=C]   at is the centre
+ *  bar at=x,y size=WxH color=C [track=C]
+ *  clickable at=x,y size=WxH
+ *  digits at=x,y size=WxH [value=N] [limit=N] [color=C]
+ *  duration at=x,y size=WxH [minutes=N] [color=C]
+ *  menu list=<ListMenu const>
+ *  title
+ *  focus <name> <name> ..                     order focus moves through, wraps around
+ * Any element can take name=<name> for the focus line, and id=<N or constant> (an ElementId)
+ * for payloads to address it by. Focus starts on the first name in the focus line,
+ * or the first element that can hold focus.
+ * Colours are r,g,b (5/6/5 bits), white/black/red/green/blue, or a constant.
+ * Constants and ids are looked up in constants.rs, 'stats::STAT_WORK' style paths from the crate root.
+ */
+pub fn compile_scene(path: &Path, source: &str, assets: &mut SceneAssets) -> Result<String, Vec<SceneError>> {
+    let error = |line: usize, message: String| SceneError { path: path.to_path_buf(), line, message };
+    let mut errors = Vec::new();
+    let mut scene: Option<String> = None;
+    let mut elements: Vec<SceneElement> = Vec::new();
+    let mut focus: Option<(usize, Vec<String>)> = None;
+    let mut animations = 0;
+    let mut transition: Option<String> = None;
+
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let text = text.split_once('#').map_or(text, |(code, _)| code).trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut directive = match Directive::parse(text) {
+            Ok(directive) => directive,
+            Err(message) => {
+                errors.push(error(line, message));
+                continue;
+            }
+        };
+
+        match directive.kind.as_str() {
+            "scene" if scene.is_some() => errors.push(error(line, "scene given twice".into())),
+            "scene" => match directive.words() {
+                Ok(words) if words.len() == 1 => scene = Some(words[0].clone()),
+                _ => errors.push(error(line, "expected 'scene <Scene variant>'".into())),
+            },
+            _ if scene.is_none() => errors.push(error(line, "the first line has to be 'scene <Scene variant>'".into())),
+            "transition" if transition.is_some() => errors.push(error(line, "transition given twice".into())),
+            "transition" => match directive.words().as_deref() {
+                Ok([kind]) if ["cut", "slide", "fade", "wipe"].contains(&kind.as_str()) => {
+                    let mut variant = kind.clone();
+                    variant[..1].make_ascii_uppercase();
+                    transition = Some(variant);
+                }
+                _ => errors.push(error(line, "expected 'transition cut|slide|fade|wipe'".into())),
+            },
+            "focus" if focus.is_some() => errors.push(error(line, "focus given twice".into())),
+            "focus" => match directive.words() {
+                Ok(words) => focus = Some((line, words)),
+                Err(message) => errors.push(error(line, message)),
+            },
+            _ => {
+                let name = directive.take("name");
+                let id = directive.take("id").map(|id| element_id(&id)).transpose();
+                let code = id.and_then(|id| {
+                    let code = compile_element(&mut directive, assets)?;
+                    directive.finish()?;
+                    Ok((id, code))
+                });
+                match code {
+                    Ok(_) if elements.len() == MAX_SCENE_ELEMENTS => {
+                        errors.push(error(line, format!("more than {} elements", MAX_SCENE_ELEMENTS)))
+                    }
+                    Ok(_) if directive.kind == "animation" && animations == MAX_ANIMATIONS => {
+                        errors.push(error(line, format!("more than {} animations, the rest wouldn't play", MAX_ANIMATIONS)))
+                    }
+                    Ok(_) if name.is_some() && elements.iter().any(|element| element.name == name) => {
+                        errors.push(error(line, format!("'{}' is already the name of another element", name.unwrap())))
+                    }
+                    Ok((Some(id), _)) if elements.iter().any(|element| element.id.as_ref() == Some(&id)) => {
+                        errors.push(error(line, format!("another element already has id {}", id)))
+                    }
+                    Ok((id, code)) => {
+                        animations += (directive.kind == "animation") as usize;
+                        elements.push(SceneElement { line, name, id, code });
+                    }
+                    Err(message) => errors.push(error(line, message)),
+                }
+            }
+        }
+    }
+
+    let Some(scene) = scene else {
+        errors.push(error(1, "empty scene, expected 'scene <Scene variant>'".into()));
+        return Err(errors);
+    };
+
+    // Positions in the focus ring, by element index
+    let mut order: Vec<usize> = Vec::new();
+    if let Some((line, names)) = &focus {
+        for name in names {
+            match elements.iter().position(|element| element.name.as_ref() == Some(name)) {
+                None => errors.push(error(*line, format!("no element named '{}'", name))),
+                Some(index) if matches!(elements[index].code, ElementCode::Fixed(_)) => {
+                    errors.push(error(*line, format!("'{}' can't hold focus", name)))
+                }
+                Some(index) if order.contains(&index) => errors.push(error(*line, format!("'{}' is in the focus order twice", name))),
+                Some(index) => order.push(index),
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let cursor = order
+        .first()
+        .copied()
+        .or_else(|| elements.iter().position(|element| !matches!(element.code, ElementCode::Fixed(_))))
+        .unwrap_or(0);
+
+    let name = path.file_stem().unwrap().to_string_lossy().to_uppercase();
+    let mut code = format!("\n// {}\npub const {}_SCENE: SceneData = SceneData {{\n", path.display(), name);
+    code.push_str(&format!("    scene: Scene::{},\n    elements: ElementList::from_array([\n", scene));
+    for (index, element) in elements.iter().enumerate() {
+        let element_code = match &element.code {
+            ElementCode::Fixed(code) | ElementCode::Focusable(code) => code.clone(),
+            ElementCode::Linked(link) => match order.iter().position(|&focused| focused == index) {
+                Some(at) => link(order[(at + 1) % order.len()], order[(at + order.len() - 1) % order.len()]),
+                // Not in the focus order, Select and Back keep focus where it is
+                None => link(index, index),
+            },
+        };
+        let id = element.id.as_ref().map_or(String::new(), |id| format!(".with_id({})", id));
+        code.push_str(&format!("        // line {}\n        SceneElement::new({}){},\n", element.line, element_code, id));
+    }
+    code.push_str(&format!("    ]),\n    cursor_index: {},\n", cursor));
+    code.push_str(&format!("    transition: Transition::{}\n}};\n", transition.as_deref().unwrap_or("Cut")));
+    Ok(code)
+}
+
+fn compile_element(directive: &mut Directive, assets: &mut SceneAssets) -> Result<ElementCode, String> {
+    let code = match directive.kind.as_str() {
+        "image" => {
+            let file = directive.require("file")?;
+            let (at, size) = (directive.point()?, directive.size()?);
+            check_bounds(at, size)?;
+            let data = assets.file(&file, (size.0 * size.1 * 2) as usize)?;
+            ElementCode::Fixed(format!(
+                "UIType::Image(ImageData::new({}, {}, {}, Point::new({}, {})))",
+                data, size.0, size.1, at.0, at.1
+            ))
+        }
+        "animation" => {
+            let file = directive.require("file")?;
+            let (at, size) = (directive.point()?, directive.size()?);
+            let frames = directive.require_number("frames")?;
+            let looping = directive.flag("loop");
+            check_bounds(at, size)?;
+            if frames == 0 {
+                return Err("an animation needs at least one frame".into());
+            }
+            let metadata = assets.animation(&file, size, frames)?;
+            ElementCode::Fixed(format!(
+                "UIType::AnimatedSprite(Animation::Sprite(AnimationIterator {{ frame_bytes: &{}, current_frame: 0, position: Point::new({}, {}), looping: {} }}))",
+                metadata, at.0, at.1, looping
+            ))
+        }
+        "label" => {
+            let at = directive.point()?;
+            let text = directive.require("text")?;
+            let color = directive.color("color")?.unwrap_or_else(|| "Rgb565::new(31, 63, 31)".into());
+            let font = directive.number("font")?.map_or(String::new(), |font| format!(".with_font_size({})", font));
+            check_bounds(at, (1, 1))?;
+            if text.len() > LABEL_CAPACITY {
+                return Err(format!("label text is longer than {} bytes", LABEL_CAPACITY));
+            }
+            ElementCode::Fixed(format!(
+                "UIType::Label(LabelElement::new(Point::new({}, {}), {:?}, {}){})",
+                at.0, at.1, text, color, font
+            ))
+        }
+        "text" => {
+            let rect = directive.rect()?;
+            let mut code = format!("TextElement::new({}, {:?})", rect, directive.require("text")?);
+            if let Some(font) = directive.number("font")? {
+                code.push_str(&format!(".with_font_size({})", font));
+            }
+            if let Some(color) = directive.color("color")? {
+                code.push_str(&format!(".with_color({})", color));
+            }
+            match directive.take("align").as_deref() {
+                None => {}
+                Some("left") => code.push_str(".with_alignment(Alignment::Left)"),
+                Some("center") => code.push_str(".with_alignment(Alignment::Center)"),
+                Some("right") => code.push_str(".with_alignment(Alignment::Right)"),
+                Some(other) => return Err(format!("align is left, center or right, not '{}'", other)),
+            }
+            ElementCode::Fixed(format!("UIType::TextBox({})", code))
+        }
+        "chart" => {
+            let rect = directive.rect()?;
+            let bar = directive.color("color")?.ok_or("missing color=")?;
+            let highlight = directive.color("highlight")?.ok_or("missing highlight=")?;
+            ElementCode::Fixed(format!("UIType::BarChart(BarChartElement::new({}, {}, {}))", rect, bar, highlight))
+        }
+        "ring" => {
+            let (x, y) = directive.point()?;
+            let radius = directive.require_number("radius")?;
+            let color = directive.color("color")?.ok_or("missing color=")?;
+            check_bounds((x - radius as i64, y - radius as i64), (2 * radius + 1, 2 * radius + 1))?;
+            let mut code = format!("ProgressRing::new(Point::new({}, {}), {}, {})", x, y, radius, color);
+            if let Some(thickness) = directive.number("thickness")? {
+                if thickness == 0 || thickness > radius {
+                    return Err(format!("thickness has to be between 1 and the radius ({})", radius));
+                }
+                code.push_str(&format!(".with_thickness({})", thickness));
+            }
+            if let Some(track) = directive.color("track")? {
+                code.push_str(&format!(".with_track_color({})", track));
+            }
+            if let Some(background) = directive.color("background")? {
+                code.push_str(&format!(".with_background({})", background));
+            }
+            ElementCode::Fixed(format!("UIType::ProgressRing({})", code))
+        }
+        "bar" => {
+            let rect = directive.rect()?;
+            let color = directive.color("color")?.ok_or("missing color=")?;
+            let track = directive.color("track")?.map_or(String::new(), |track| format!(".with_track_color({})", track));
+            ElementCode::Fixed(format!("UIType::ProgressBar(ProgressBar::new({}, {}){})", rect, color, track))
+        }
+        "clickable" => {
+            let rect = directive.rect()?;
+            ElementCode::Linked(Box::new(move |next, prev| {
+                format!("UIType::Clickable(ClickableElement::new({}, {}, {}))", rect, next, prev)
+            }))
+        }
+        "digits" => {
+            let rect = directive.rect()?;
+            let value = directive.number("value")?.unwrap_or(0);
+            let limit = directive.number("limit")?;
+            let color = directive.color("color")?;
+            if limit.is_some_and(|limit| limit == 0 || limit > 10) {
+                return Err("limit has to be between 1 and 10".into());
+            }
+            if value >= limit.unwrap_or(10) {
+                return Err(format!("value {} is past the limit", value));
+            }
+            let mut options = String::new();
+            if let Some(limit) = limit {
+                options.push_str(&format!(".with_limit({})", limit));
+            }
+            if let Some(color) = color {
+                options.push_str(&format!(".with_color({})", color));
+            }
+            ElementCode::Linked(Box::new(move |next, prev| {
+                format!("UIType::Digits(DigitsElement::new({}, {}, {}, {}){})", rect, value, next, prev, options)
+            }))
+        }
+        "duration" => {
+            let rect = directive.rect()?;
+            let minutes = directive.number("minutes")?.unwrap_or(0);
+            let color = directive.color("color")?;
+            if minutes > 99 * 60 + 59 {
+                return Err("the editor only goes up to 99:59".into());
+            }
+            let options = color.map_or(String::new(), |color| format!(".with_color({})", color));
+            ElementCode::Linked(Box::new(move |next, prev| {
+                format!(
+                    "UIType::Duration(DurationEditor::new({}, embassy_time::Duration::from_secs({}), {}, {}){})",
+                    rect, minutes * 60, next, prev, options
+                )
+            }))
+        }
+        "menu" => ElementCode::Focusable(format!("UIType::Menu({})", rust_value(&directive.require("list")?)?)),
+        "title" => ElementCode::Fixed("UIType::Title".into()),
+        other => return Err(format!("unknown element '{}'", other)),
+    };
+    Ok(code)
+}
+
+fn check_bounds((x, y): (i64, i64), (width, height): (u32, u32)) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err("size can't be zero".into());
+    }
+    if x < 0 || y < 0 || x + width as i64 > DISPLAY_WIDTH as i64 || y + height as i64 > DISPLAY_HEIGHT as i64 {
+        return Err(format!(
+            "{}x{} at {},{} doesn't fit on the {}x{} display",
+            width, height, x, y, DISPLAY_WIDTH as i64, DISPLAY_HEIGHT as i64
+        ));
+    }
+    Ok(())
+}
+
+// ElementId from a number, or the constant holding one
+fn element_id(value: &str) -> Result<String, String> {
+    match value.parse::<i64>() {
+        Ok(id) if (0..=255).contains(&id) => Ok(format!("ElementId({})", id)),
+        Ok(_) => Err(format!("id={} doesn't fit in a u8", value)),
+        Err(_) => rust_value(value),
+    }
+}
+
+// Number as is, a constant from constants.rs, or a path from the crate root
+fn rust_value(value: &str) -> Result<String, String> {
+    if value.parse::<i64>().is_ok() {
+        return Ok(value.to_string());
+    }
+    let is_path = value
+        .split("::")
+        .all(|part| part.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    match (is_path, value.contains("::")) {
+        (false, _) => Err(format!("'{}' isn't a number or a constant", value)),
+        (true, false) => Ok(format!("crate::constants::{}", value)),
+        (true, true) => Ok(format!("crate::{}", value)),
+    }
+}
+
+// A line split into its kind and key=value arguments, bare words are kept as flags
+struct Directive {
+    kind: String,
+    args: Vec<(String, Option<String>)>,
+}
+
+impl Directive {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut token = String::new();
+        let mut quoted = false;
+        let mut chars = text.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => quoted = !quoted,
+                '\\' if quoted => token.push(chars.next().ok_or("line ends in the middle of an escape")?),
+                c if c.is_whitespace() && !quoted => {
+                    if !token.is_empty() {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+                }
+                c => token.push(c),
+            }
+        }
+        if quoted {
+            return Err("unclosed quote".into());
+        }
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+
+        let mut tokens = tokens.into_iter();
+        let kind = tokens.next().unwrap_or_default();
+        let args = tokens
+            .map(|token| match token.split_once('=') {
+                Some((key, value)) => (key.to_string(), Some(value.to_string())),
+                None => (token, None),
+            })
+            .collect();
+        Ok(Directive { kind, args })
+    }
+
+    // Arguments that are plain words, like the names on a focus line
+    fn words(&mut self) -> Result<Vec<String>, String> {
+        std::mem::take(&mut self.args)
+            .into_iter()
+            .map(|(word, value)| match value {
+                None => Ok(word),
+                Some(_) => Err(format!("unexpected {}= on a {} line", word, self.kind)),
+            })
+            .collect()
+    }
+
+    fn take(&mut self, key: &str) -> Option<String> {
+        let index = self.args.iter().position(|(name, value)| name == key && value.is_some())?;
+        self.args.remove(index).1
+    }
+
+    fn require(&mut self, key: &str) -> Result<String, String> {
+        self.take(key).ok_or_else(|| format!("missing {}=", key))
+    }
+
+    fn flag(&mut self, key: &str) -> bool {
+        let index = self.args.iter().position(|(name, value)| name == key && value.is_none());
+        index.map(|index| self.args.remove(index)).is_some()
+    }
+
+    fn number(&mut self, key: &str) -> Result<Option<u32>, String> {
+        self.take(key)
+            .map(|value| value.parse().map_err(|_| format!("{}={} isn't a whole number", key, value)))
+            .transpose()
+    }
+
+    fn require_number(&mut self, key: &str) -> Result<u32, String> {
+        self.number(key)?.ok_or_else(|| format!("missing {}=", key))
+    }
+
+    fn point(&mut self) -> Result<(i64, i64), String> {
+        let value = self.require("at")?;
+        let parsed = value.split_once(',').and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)));
+        parsed.ok_or_else(|| format!("at={} isn't x,y", value))
+    }
+
+    fn size(&mut self) -> Result<(u32, u32), String> {
+        let value = self.require("size")?;
+        let parsed = value.split_once('x').and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+        parsed.ok_or_else(|| format!("size={} isn't WxH", value))
+    }
+
+    // at= and size= as a Rectangle, checked against the display
+    fn rect(&mut self) -> Result<String, String> {
+        let (at, size) = (self.point()?, self.size()?);
+        check_bounds(at, size)?;
+        Ok(format!("Rectangle::new(Point::new({}, {}), Size::new({}, {}))", at.0, at.1, size.0, size.1))
+    }
+
+    fn color(&mut self, key: &str) -> Result<Option<String>, String> {
+        let Some(value) = self.take(key) else {
+            return Ok(None);
+        };
+        let named = match value.as_str() {
+            "white" => Some((31, 63, 31)),
+            "black" => Some((0, 0, 0)),
+            "red" => Some((31, 0, 0)),
+            "green" => Some((0, 63, 0)),
+            "blue" => Some((0, 0, 31)),
+            _ => None,
+        };
+        let components: Vec<&str> = value.split(',').collect();
+        let (r, g, b) = match (named, components.as_slice()) {
+            (Some(rgb), _) => rgb,
+            (None, [r, g, b]) => {
+                let parse = |c: &str| c.trim().parse::<u8>().map_err(|_| format!("{}={} isn't r,g,b", key, value));
+                (parse(r)?, parse(g)?, parse(b)?)
+            }
+            (None, _) => return rust_value(&value).map(Some),
+        };
+        if r > 31 || g > 63 || b > 31 {
+            return Err(format!("{}={} is out of range, Rgb565 goes up to 31,63,31", key, value));
+        }
+        Ok(Some(format!("Rgb565::new({}, {}, {})", r, g, b)))
+    }
+
+    // Anything left over wasn't understood
+    fn finish(&self) -> Result<(), String> {
+        match self.args.first() {
+            None => Ok(()),
+            Some((key, Some(_))) => Err(format!("unknown argument {}= for {}", key, self.kind)),
+            Some((word, None)) => Err(format!("unexpected '{}' on a {} line", word, self.kind)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(source: &str) -> Result<String, Vec<String>> {
+        compile_scene(Path::new("scenes/test.scene"), source, &mut SceneAssets::default())
+            .map_err(|errors| errors.iter().map(|error| error.to_string()).collect())
+    }
+
+    // The one error a broken scene gives
+    fn error(source: &str) -> String {
+        let mut errors = compile(source).expect_err("scene compiled");
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        errors.remove(0)
+    }
+
+    #[test]
+    fn compiles_a_scene() {
+        let code = compile("# A comment\nscene Settings\ntransition fade\n\ntitle\nclickable name=a at=0,0 size=10x10\n").unwrap();
+        assert!(code.contains("pub const TEST_SCENE: SceneData"));
+        assert!(code.contains("scene: Scene::Settings"));
+        assert!(code.contains("transition: Transition::Fade"));
+        // Focus starts on the first element that can hold it
+        assert!(code.contains("cursor_index: 1,"));
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        assert_eq!(
+            error("scene MainMenu\n\nbar at=300,0 size=40x8 color=white"),
+            "scenes/test.scene:3: 40x8 at 300,0 doesn't fit on the 320x240 display"
+        );
+        assert_eq!(
+            error("scene MainMenu\nimage file=missing.bin at=0,0 size=4x4"),
+            "scenes/test.scene:2: asset missing.bin not found in src/assets"
+        );
+        assert_eq!(
+            error("scene MainMenu\nlabel at=0,0 text=\"unclosed"),
+            "scenes/test.scene:2: unclosed quote"
+        );
+        assert_eq!(
+            error("scene MainMenu\ntitle\nsparkles at=0,0"),
+            "scenes/test.scene:3: unknown element 'sparkles'"
+        );
+        assert_eq!(
+            error("scene MainMenu\nbar at=0,0 size=10x10 color=40,0,0"),
+            "scenes/test.scene:2: color=40,0,0 is out of range, Rgb565 goes up to 31,63,31"
+        );
+    }
+
+    #[test]
+    fn scene_has_to_come_first() {
+        assert_eq!(compile("title\n").unwrap_err(), [
+            "scenes/test.scene:1: the first line has to be 'scene <Scene variant>'",
+            "scenes/test.scene:1: empty scene, expected 'scene <Scene variant>'",
+        ]);
+        assert_eq!(error("# nothing\n"), "scenes/test.scene:1: empty scene, expected 'scene <Scene variant>'");
+    }
+
+    #[test]
+    fn every_error_is_reported() {
+        let errors = compile("scene MainMenu\nbar at=0,0 size=0x8 color=white\ntitle\nring at=5,5 radius=10 color=white").unwrap_err();
+        assert_eq!(errors, [
+            "scenes/test.scene:2: size can't be zero",
+            "scenes/test.scene:4: 21x21 at -5,-5 doesn't fit on the 320x240 display",
+        ]);
+    }
+
+    #[test]
+    fn limits_come_from_the_crate() {
+        let label = format!("scene MainMenu\nlabel at=0,0 text=\"{}\"", "x".repeat(LABEL_CAPACITY + 1));
+        assert_eq!(error(&label), format!("scenes/test.scene:2: label text is longer than {} bytes", LABEL_CAPACITY));
+
+        let full = format!("scene MainMenu\n{}", "title\n".repeat(MAX_SCENE_ELEMENTS + 1));
+        assert_eq!(
+            error(&full),
+            format!("scenes/test.scene:{}: more than {} elements", MAX_SCENE_ELEMENTS + 2, MAX_SCENE_ELEMENTS)
+        );
+    }
+
+    #[test]
+    fn focus_names_are_checked() {
+        let scene = "scene MainMenu\ntitle name=header\nclickable name=a at=0,0 size=10x10\nfocus a b header";
+        let errors = compile(scene).unwrap_err();
+        assert_eq!(errors, [
+            "scenes/test.scene:4: no element named 'b'",
+            "scenes/test.scene:4: 'header' can't hold focus",
+        ]);
+    }
+
+    #[test]
+    fn assets_are_included_once() {
+        let mut assets = SceneAssets::default();
+        let scene = "scene MainMenu\nanimation file=dice_rgb565.bin at=0,0 size=110x75 frames=24\nanimation file=dice_rgb565.bin at=0,80 size=110x75 frames=24";
+        assert!(compile_scene(Path::new("a.scene"), scene, &mut assets).is_ok());
+        assert_eq!(assets.code.matches("include_bytes!").count(), 1);
+        assert_eq!(assets.code.matches("AnimationMetadata::new").count(), 1);
+
+        let too_short = "scene MainMenu\nanimation file=dice_rgb565.bin at=0,0 size=110x75 frames=100";
+        let errors = compile_scene(Path::new("b.scene"), too_short, &mut assets).err().unwrap();
+        assert!(errors[0].to_string().starts_with("b.scene:2: asset dice_rgb565.bin is "), "{}", errors[0]);
+    }
+}
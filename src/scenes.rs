@@ -0,0 +1,2 @@
+// Scenes compiled by build.rs from src/assets/scenes, one <NAME>_SCENE per file
+include!(concat!(env!("OUT_DIR"), "/scenes.rs"));
@@ -1,36 +1,190 @@
-use embedded_graphics::{Drawable, image::{Image, ImageRaw, ImageRawLE}, pixelcolor::Rgb565, prelude::{Dimensions, Point, RgbColor, Size}, primitives::{PrimitiveStyleBuilder, Rectangle, StrokeAlignment, StyledDimensions, StyledDrawable}, text::Text};
-use embedded_graphics_framebuf::FrameBuf;
-use embedded_ttf::FontTextStyleBuilder;
-use rusttype::Font;
-use crate::{animations::{Animation, AnimationState, FrameType}, clickable::ClickableElement, constants::{MAX_ANIMATIONS, MENU_HEADER_DATA, TEST_SCENE}, text_box::TextElement};
+use core::ops::{Deref, DerefMut};
+use embedded_graphics::{Drawable, image::{Image, ImageRaw, ImageRawLE}, pixelcolor::Rgb565, prelude::{Point, RgbColor, Size}, primitives::Rectangle, text::{Baseline, Text}};
+use eg_seven_segment::SevenSegmentStyleBuilder;
+use crate::{animations::{Animation, AnimationState, CursorMove, FrameType}, clickable::ClickableElement, duration_editor::DurationEditor, list_menu::ListMenu, progress::{ProgressBar, ProgressRing}, constants::{MAX_ANIMATIONS, MAX_SCENE_ELEMENTS}, scenes::TEST_SCENE, stats::BarChartElement, text_box::{LabelElement, TextElement}, transitions::Transition};
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub enum Scene {
     #[default]
     MainMenu,
     ConfigTaro,
     ConfigTaroPlus,
-    ConfigCountingUp,
+    Statistics,
+    Settings,
+    // Work, break and pause timers
+    Session,
 }
 
 pub trait UINode {
     fn get_position(&self) -> &Rectangle;
 
+    // Where the focus highlight goes while this element has focus
+    fn focus_rect(&self) -> Rectangle {
+        *self.get_position()
+    }
+
     fn handle_action(&mut self, scene: &mut SceneData, action: UIAction);
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum UIType {
-    Menu(),
+    Menu(ListMenu),
     Clickable(ClickableElement),
     Digits(DigitsElement),
+    Duration(DurationEditor),
     AnimatedSprite(Animation),
     TextBox(TextElement),
+    Label(LabelElement),
+    BarChart(BarChartElement),
+    ProgressRing(ProgressRing),
+    ProgressBar(ProgressBar),
     Image(ImageData),
     Title,
     Empty
 }
 
+impl UIType {
+    // Elements that can hold focus and receive UIActions
+    pub fn as_node(&self) -> Option<&dyn UINode> {
+        match self {
+            UIType::Clickable(element) => Some(element),
+            UIType::Digits(element) => Some(element),
+            UIType::Duration(element) => Some(element),
+            UIType::Menu(element) => Some(element),
+            _ => None
+        }
+    }
+
+    pub fn as_node_mut(&mut self) -> Option<&mut dyn UINode> {
+        match self {
+            UIType::Clickable(element) => Some(element),
+            UIType::Digits(element) => Some(element),
+            UIType::Duration(element) => Some(element),
+            UIType::Menu(element) => Some(element),
+            _ => None
+        }
+    }
+
+    // Everything the element can draw to, None for elements that don't draw anything
+    pub fn area(&self) -> Option<Rectangle> {
+        match self {
+            UIType::Menu(element) => Some(element.position),
+            UIType::Clickable(element) => Some(element.position),
+            UIType::Digits(element) => Some(element.position),
+            UIType::Duration(element) => Some(element.position),
+            UIType::TextBox(element) => Some(element.position),
+            UIType::BarChart(element) => Some(element.position),
+            UIType::ProgressRing(element) => Some(element.area()),
+            UIType::ProgressBar(element) => Some(element.position),
+            UIType::Label(element) => Some(element.area()),
+            UIType::Image(image) => Some(Rectangle::new(image.position, Size::new(image.width, image.height))),
+            UIType::AnimatedSprite(Animation::Sprite(sprite)) => Some(Rectangle::new(
+                sprite.position,
+                Size::new(sprite.frame_bytes.width as u32, sprite.frame_bytes.height as u32)
+            )),
+            UIType::Title => Some(Rectangle::new(TITLE_POSITION, Size::new(TITLE_WIDTH, TITLE_HEIGHT))),
+            UIType::AnimatedSprite(_) | UIType::Empty => None
+        }
+    }
+}
+
+// Stays the same however a scene's elements are ordered, so payloads can address an element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementId(pub u8);
+
+#[derive(Debug, Clone, Copy)]
+pub struct SceneElement {
+    pub id: Option<ElementId>,
+    pub ui: UIType
+}
+
+impl SceneElement {
+    pub const fn new(ui: UIType) -> Self {
+        SceneElement { id: None, ui }
+    }
+
+    pub const fn with_id(self, id: ElementId) -> Self {
+        SceneElement { id: Some(id), ..self }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SceneError {
+    // Already holds MAX_SCENE_ELEMENTS
+    Full,
+    // Sprites past MAX_ANIMATIONS that won't play
+    TooManyAnimations(usize)
+}
+
+/*
+ * Up to MAX_SCENE_ELEMENTS elements, stored inline so scenes stay Copy and can be consts.
+ * Derefs to a slice of the elements in use.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct ElementList {
+    elements: [SceneElement; MAX_SCENE_ELEMENTS],
+    len: u8
+}
+
+impl ElementList {
+    pub const fn new() -> Self {
+        ElementList { elements: [SceneElement::new(UIType::Empty); MAX_SCENE_ELEMENTS], len: 0 }
+    }
+
+    // More than MAX_SCENE_ELEMENTS elements is a compile error
+    pub const fn from_array<const N: usize>(elements: [SceneElement; N]) -> Self {
+        const { assert!(N <= MAX_SCENE_ELEMENTS, "scene has more than MAX_SCENE_ELEMENTS elements") };
+
+        let mut list = Self::new();
+        while (list.len as usize) < N {
+            list.elements[list.len as usize] = elements[list.len as usize];
+            list.len += 1;
+        }
+        list
+    }
+
+    pub fn push(&mut self, element: SceneElement) -> Result<(), SceneError> {
+        let slot = self.elements.get_mut(self.len as usize).ok_or(SceneError::Full)?;
+        *slot = element;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn find(&self, id: ElementId) -> Option<&SceneElement> {
+        self.iter().find(|element| element.id == Some(id))
+    }
+
+    pub fn find_mut(&mut self, id: ElementId) -> Option<&mut SceneElement> {
+        self.iter_mut().find(|element| element.id == Some(id))
+    }
+}
+
+impl Default for ElementList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for ElementList {
+    type Target = [SceneElement];
+
+    fn deref(&self) -> &Self::Target {
+        &self.elements[..self.len as usize]
+    }
+}
+
+impl DerefMut for ElementList {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.elements[..self.len as usize]
+    }
+}
+
+// Header image drawn by UIType::Title
+const MENU_HEADER_DATA: &[u8] = include_bytes!("./assets/Menu_Header.bmp");
+const TITLE_POSITION: Point = Point::new(138, 11);
+const TITLE_WIDTH: u32 = 176;
+const TITLE_HEIGHT: u32 = 40;
+
 #[derive(Debug, Clone, Copy)]
 pub struct ImageData {
     pub data: &'static [u8],
@@ -53,41 +207,13 @@ impl Drawable for UIType {
         where
             D: embedded_graphics::prelude::DrawTarget<Color = Self::Color> {
         match self {
-            UIType::TextBox(text_element) => {
-                let background_style = PrimitiveStyleBuilder::new()
-                    .fill_color(Rgb565::new(
-                            222, 
-                            221, 
-                            218))
-                    .stroke_width(2)
-                    .stroke_color(Rgb565::BLACK)
-                    .build();
-                text_element.position.draw_styled(&background_style, target)?;
-
-                let top_bar = Rectangle::new(
-                    text_element.position.top_left,
-                    Size::new(
-                        text_element.position.size.width, 
-                        10)
-                    );
-                let style = PrimitiveStyleBuilder::new()
-                    .fill_color(Rgb565::new(
-                            192, 
-                            191, 
-                            188))
-                    .stroke_width(2)
-                    .stroke_color(Rgb565::BLACK)
-                    .build();
-                top_bar.draw_styled(&style, target)
-            },
+            UIType::TextBox(text_element) => text_element.draw(target),
             UIType::Title => {
-                const HEADER_WIDTH: u32 = 176;
-
-                let raw_image: ImageRawLE<Rgb565> = ImageRaw::new(MENU_HEADER_DATA, HEADER_WIDTH);
+                let raw_image: ImageRawLE<Rgb565> = ImageRaw::new(MENU_HEADER_DATA, TITLE_WIDTH);
 
                 let image = Image::new(
                     &raw_image,
-                    Point::new(138, 11)
+                    TITLE_POSITION
                 );
 
                 image.draw(target)
@@ -101,16 +227,67 @@ impl Drawable for UIType {
                 let image = Image::new(&raw_image, image_data.position);
                 image.draw(target)
             }
+            // Blended into the frame buffer by the renderer, see LabelElement::blend
+            UIType::Label(_) => Ok(()),
+            UIType::BarChart(chart) => chart.draw(target),
+            UIType::ProgressRing(ring) => ring.draw(target),
+            UIType::ProgressBar(bar) => bar.draw(target),
+            UIType::Digits(digit) => digit.draw(target),
+            UIType::Duration(editor) => editor.draw(target),
+            UIType::Menu(menu) => menu.draw(target),
             _ => Ok(())
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum UIAction {
     Back,
     Select,
-    MoveBack,
-    MoveNext
+    MoveBack(Turn),
+    MoveNext(Turn),
+    // Revert the last state switch (double click)
+    Undo,
+    // Leave whatever is running and go to the main menu (multi-tap)
+    Home
+}
+
+/*
+ * How far the encoder was turned.
+ * Acceleration is opt-in: most elements step by 'detents',
+ * ones with a long range to cover can use 'accelerated' instead.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Turn {
+    pub detents: u8,
+    // Detents scaled by how fast the knob was spun
+    pub accelerated: u8
+}
+
+impl Turn {
+    // Unsigned, the direction is in the UIAction
+    pub const fn new(detents: i16, accelerated: i16) -> Self {
+        Turn { detents: clamp_u8(detents.unsigned_abs()), accelerated: clamp_u8(accelerated.unsigned_abs()) }
+    }
+
+    // A turn without acceleration, e.g. a single detent
+    pub const fn steps(detents: u8) -> Self {
+        Turn { detents, accelerated: detents }
+    }
+}
+
+const fn clamp_u8(value: u16) -> u8 {
+    if value > u8::MAX as u16 { u8::MAX } else { value as u8 }
+}
+
+/*
+ * What an action changed on screen: the element that has to be redrawn,
+ * and where the focus highlight moved from and to
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ActionOutcome {
+    pub redraw: Option<Rectangle>,
+    pub focus_moved: Option<(Rectangle, Rectangle)>
 }
 
 #[derive(Default)]
@@ -120,20 +297,96 @@ pub struct SceneManager {
 }
 
 impl SceneManager {
-    pub fn initialize_scene(&mut self, new_scene: SceneData) {
+    // The scene is loaded either way, the error says which of its sprites had no room to play
+    pub fn initialize_scene(&mut self, new_scene: SceneData) -> Result<(), SceneError> {
         self.current_scene = new_scene;
         self.animation_queue = AnimationState::default();
 
         let mut animation_count = 0;
         self.current_scene.elements.iter().for_each(|element|
         {
-            if let UIType::AnimatedSprite(element_data) = element {
+            if let UIType::AnimatedSprite(element_data) = element.ui {
                 if animation_count < MAX_ANIMATIONS {
-                    self.animation_queue.queue[animation_count] = *element_data;
-                    animation_count += 1;
+                    self.animation_queue.queue[animation_count] = element_data;
                 }
+                animation_count += 1;
             } 
         });
+
+        match animation_count.checked_sub(MAX_ANIMATIONS) {
+            Some(dropped) if dropped > 0 => Err(SceneError::TooManyAnimations(dropped)),
+            _ => Ok(())
+        }
+    }
+
+    // Swaps in a new version of the element with this id, returns the area to redraw
+    pub fn replace_element(&mut self, id: ElementId, ui: UIType) -> Option<Rectangle> {
+        let element = self.current_scene.elements.find_mut(id)?;
+        let old = core::mem::replace(&mut element.ui, ui);
+
+        match (old.area(), ui.area()) {
+            (Some(old), Some(new)) => {
+                // Both, the new version can be smaller than what's left of the old one
+                let top_left = old.top_left.component_min(new.top_left);
+                let bottom_right = (old.top_left + old.size).component_max(new.top_left + new.size);
+                Some(Rectangle::with_corners(top_left, bottom_right - Point::new(1, 1)))
+            }
+            (old, new) => old.or(new)
+        }
+    }
+
+    // Rectangle of the element holding focus, if it can hold focus at all
+    pub fn focused_rect(&self) -> Option<Rectangle> {
+        self.current_scene.elements
+            .get(self.current_scene.cursor_index as usize)?
+            .ui
+            .as_node()
+            .map(|node| node.focus_rect())
+    }
+
+    // Hands the action to the focused element and slides the highlight if focus moved
+    pub fn handle_action(&mut self, action: UIAction) -> ActionOutcome {
+        let index = self.current_scene.cursor_index as usize;
+        let Some(mut element) = self.current_scene.elements.get(index).map(|element| element.ui) else {
+            return ActionOutcome::default();
+        };
+        let Some(node) = element.as_node_mut() else {
+            return ActionOutcome::default();
+        };
+
+        // The element needs the scene to move focus, so work on a copy and put it back
+        let area = *node.get_position();
+        let old_rect = node.focus_rect();
+        node.handle_action(&mut self.current_scene, action);
+        self.current_scene.elements[index].ui = element;
+
+        let mut outcome = ActionOutcome { redraw: Some(area), focus_moved: None };
+        match self.focused_rect() {
+            // Either another element took focus, or the highlight moved inside this one
+            Some(new_rect) if new_rect != old_rect => {
+                outcome.focus_moved = Some((old_rect, new_rect));
+                self.queue_cursor(old_rect, new_rect);
+            }
+            Some(_) => {}
+            // Focus went somewhere that can't hold it, stay where we were
+            None => self.current_scene.cursor_index = index as u8
+        }
+        outcome
+    }
+
+    // Only one highlight at a time, a new move carries on from wherever the last one got to
+    fn queue_cursor(&mut self, start: Rectangle, end: Rectangle) {
+        let queue = &mut self.animation_queue.queue;
+        let in_flight = queue.iter().position(|a| matches!(a, Animation::Cursor(_)));
+        let start = match in_flight.map(|slot| queue[slot]) {
+            Some(Animation::Cursor(moving)) => moving.cursor_rect,
+            _ => start
+        };
+        let slot = in_flight.or_else(|| queue.iter().position(|a| matches!(a, Animation::Empty)));
+
+        if let Some(slot) = slot {
+            queue[slot] = Animation::Cursor(CursorMove::initialize(start, end));
+        }
     }
 
     pub fn play_next(&mut self) -> [FrameType; 6] {
@@ -168,8 +421,10 @@ impl SceneManager {
 #[derive(Debug, Clone, Copy)]
 pub struct SceneData {
     pub scene: Scene,
-    pub elements: [UIType; 10],
+    pub elements: ElementList,
     pub cursor_index: u8,
+    // How this scene replaces the one before it
+    pub transition: Transition,
 }
 
 impl Default for SceneData {
@@ -179,14 +434,54 @@ impl Default for SceneData {
 }
 
 
+// Single seven-segment digit the encoder turns through 0..limit
 #[derive(Debug, Clone, Copy)]
 pub struct DigitsElement {
     pub position: Rectangle,
     pub current_digit: u8,
+    // One past the highest value, e.g. 6 for the tens of minutes
+    pub limit: u8,
+    pub color: Rgb565,
     next_element: u8,
     prev_element: u8
 }
 
+impl DigitsElement {
+    pub const fn new(position: Rectangle, current_digit: u8, next_element: u8, prev_element: u8) -> Self {
+        DigitsElement { position, current_digit, limit: 10, color: Rgb565::WHITE, next_element, prev_element }
+    }
+
+    pub const fn with_limit(self, limit: u8) -> Self {
+        DigitsElement { limit, current_digit: self.current_digit % limit, ..self }
+    }
+
+    pub const fn with_color(self, color: Rgb565) -> Self {
+        DigitsElement { color, ..self }
+    }
+}
+
+impl Drawable for DigitsElement {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: embedded_graphics::prelude::DrawTarget<Color = Self::Color> {
+        let style = SevenSegmentStyleBuilder::new()
+            .digit_size(self.position.size)
+            .segment_width((self.position.size.width / 6).max(1))
+            .segment_color(self.color)
+            .build();
+
+        let mut digit = [0u8; 4];
+        let digit = char::from_digit(self.current_digit as u32 % 10, 10)
+            .unwrap_or('0')
+            .encode_utf8(&mut digit);
+        Text::with_baseline(digit, self.position.top_left, style, Baseline::Top).draw(target)?;
+        Ok(())
+    }
+}
+
 impl UINode for DigitsElement 
 {
    fn get_position(&self) -> &Rectangle {
@@ -195,17 +490,12 @@ impl UINode for DigitsElement
 
    fn handle_action(&mut self, scene: &mut SceneData, action: UIAction) {
        match action {
-            UIAction::MoveBack => {
-                if self.current_digit == 0 {
-                    self.current_digit = 9;
-                }
-                self.current_digit -= 1;
+            // Stops at 0 and limit - 1, several detents at once never wrap around
+            UIAction::MoveBack(turn) => {
+                self.current_digit = self.current_digit.saturating_sub(turn.detents);
             }
-            UIAction::MoveNext => {
-                if self.current_digit == 9 {
-                    self.current_digit = 0;
-                }
-                self.current_digit += 1;
+            UIAction::MoveNext(turn) => {
+                self.current_digit = self.current_digit.saturating_add(turn.detents).min(self.limit - 1);
             }
             UIAction::Select => {
                 scene.cursor_index = self.next_element;
@@ -213,6 +503,7 @@ impl UINode for DigitsElement
             UIAction::Back => {
                 scene.cursor_index = self.prev_element;
             }
+            UIAction::Undo | UIAction::Home => {}
        }
    }
 }
@@ -0,0 +1,271 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Primitive, Size},
+    primitives::{PrimitiveStyle, Rectangle},
+    Drawable,
+};
+
+use crate::{
+    scenes::STATS_SCENE,
+    history::{IntervalKind, SessionRecord},
+    scenes_util::{ElementId, SceneData, UIType},
+};
+
+const SECONDS_PER_DAY: u32 = 86_400;
+pub const STATS_DAYS: usize = 7;
+
+/*
+ * Totals shown on the statistics scene, built from the session history
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StatsSummary {
+    pub today_work_secs: u32,
+    pub today_break_secs: u32,
+    pub today_pause_secs: u32,
+    // Work intervals finished today
+    pub today_sessions: u16,
+    // Work minutes per day, oldest first, today last
+    pub week_work_mins: [u16; STATS_DAYS],
+    // Days are calendar days, otherwise 24h stretches of the device being on
+    pub calendar_days: bool,
+}
+
+impl StatsSummary {
+    // 'now' is a timestamp on the history log's timeline.
+    // Intervals count towards the day they ended on, ones after 'now' are left out.
+    pub fn from_records(records: impl Iterator<Item = SessionRecord>, now: u32) -> Self {
+        let today = now / SECONDS_PER_DAY;
+        let mut summary = StatsSummary::default();
+        let mut week_work_secs = [0u32; STATS_DAYS];
+
+        for record in records {
+            let day = record.end / SECONDS_PER_DAY;
+            if day > today {
+                continue;
+            }
+
+            let days_ago = (today - day) as usize;
+            if days_ago >= STATS_DAYS {
+                continue;
+            }
+
+            match record.kind {
+                IntervalKind::Work => {
+                    week_work_secs[STATS_DAYS - 1 - days_ago] += record.duration;
+                    if days_ago == 0 {
+                        summary.today_work_secs += record.duration;
+                        summary.today_sessions += 1;
+                    }
+                }
+                IntervalKind::Break => {
+                    if days_ago == 0 {
+                        summary.today_break_secs += record.duration;
+                    }
+                }
+                IntervalKind::Pause => {
+                    if days_ago == 0 {
+                        summary.today_pause_secs += record.duration;
+                    }
+                }
+            }
+        }
+
+        for (minutes, seconds) in summary.week_work_mins.iter_mut().zip(week_work_secs) {
+            *minutes = (seconds / 60).min(u16::MAX as u32) as u16;
+        }
+        summary
+    }
+
+    pub const fn with_calendar_days(self, calendar_days: bool) -> Self {
+        StatsSummary { calendar_days, ..self }
+    }
+}
+
+// STATS_SCENE with its labels and chart filled in
+pub fn stats_scene(summary: &StatsSummary) -> SceneData {
+    let mut scene = STATS_SCENE;
+
+    for element in scene.elements.iter_mut() {
+        match (element.id, &mut element.ui) {
+            // Without a clock there's no telling when a day starts, only how long the device was on
+            (Some(STAT_TITLE), UIType::Label(label)) => {
+                label.set_fmt(format_args!("{}", if summary.calendar_days { "Today" } else { "Day of use" }));
+            }
+            (Some(STAT_WORK), UIType::Label(label)) => {
+                label.set_fmt(format_args!("Work   {}", Hm(summary.today_work_secs)));
+            }
+            (Some(STAT_BREAK), UIType::Label(label)) => {
+                label.set_fmt(format_args!("Break  {}", Hm(summary.today_break_secs)));
+            }
+            (Some(STAT_PAUSE), UIType::Label(label)) => {
+                label.set_fmt(format_args!("Pause  {}", Hm(summary.today_pause_secs)));
+            }
+            (Some(STAT_SESSIONS), UIType::Label(label)) => {
+                label.set_fmt(format_args!("Sessions {}", summary.today_sessions));
+            }
+            (_, UIType::BarChart(chart)) => {
+                chart.values = summary.week_work_mins;
+            }
+            _ => {}
+        }
+    }
+    scene
+}
+
+// Label ids used by STATS_SCENE
+pub const STAT_WORK: ElementId = ElementId(1);
+pub const STAT_BREAK: ElementId = ElementId(2);
+pub const STAT_SESSIONS: ElementId = ElementId(3);
+pub const STAT_PAUSE: ElementId = ElementId(4);
+pub const STAT_TITLE: ElementId = ElementId(5);
+
+// Seconds shown as "HH:MM"
+struct Hm(u32);
+
+impl core::fmt::Display for Hm {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 3600, (self.0 % 3600) / 60)
+    }
+}
+
+/*
+ * Vertical bars scaled to the largest value, the last bar highlighted
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct BarChartElement {
+    pub position: Rectangle,
+    pub values: [u16; STATS_DAYS],
+    pub bar_color: Rgb565,
+    pub highlight_color: Rgb565,
+}
+
+impl BarChartElement {
+    pub const fn new(position: Rectangle, bar_color: Rgb565, highlight_color: Rgb565) -> Self {
+        BarChartElement {
+            position,
+            values: [0; STATS_DAYS],
+            bar_color,
+            highlight_color
+        }
+    }
+}
+
+impl Drawable for BarChartElement {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        const GAP: u32 = 6;
+
+        let max = self.values.iter().copied().max().unwrap_or(0).max(1) as u32;
+        let slot_width = self.position.size.width / STATS_DAYS as u32;
+        let bar_width = slot_width.saturating_sub(GAP).max(1);
+        // Leave a 2px baseline at the bottom
+        let max_height = self.position.size.height.saturating_sub(2);
+        let bottom = self.position.top_left.y + self.position.size.height as i32;
+
+        for (index, value) in self.values.iter().enumerate() {
+            let height = (*value as u32 * max_height / max).max(if *value > 0 { 1 } else { 0 });
+            let x = self.position.top_left.x + (index as u32 * slot_width + GAP / 2) as i32;
+            let color = if index == STATS_DAYS - 1 { self.highlight_color } else { self.bar_color };
+
+            Rectangle::new(
+                Point::new(x, bottom - 2 - height as i32),
+                Size::new(bar_width, height)
+            )
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(target)?;
+        }
+
+        Rectangle::new(
+            Point::new(self.position.top_left.x, bottom - 2),
+            Size::new(self.position.size.width, 2)
+        )
+        .into_styled(PrimitiveStyle::with_fill(self.bar_color))
+        .draw(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Noon on day 100 of the timeline
+    const NOW: u32 = 100 * SECONDS_PER_DAY + SECONDS_PER_DAY / 2;
+
+    fn record(kind: IntervalKind, end: u32, duration: u32) -> SessionRecord {
+        SessionRecord::new(kind, end - duration, end)
+    }
+
+    fn summary(records: &[SessionRecord]) -> StatsSummary {
+        StatsSummary::from_records(records.iter().copied(), NOW)
+    }
+
+    #[test]
+    fn totals_go_by_kind() {
+        let summary = summary(&[
+            record(IntervalKind::Work, NOW - 3600, 1500),
+            record(IntervalKind::Break, NOW - 3000, 300),
+            record(IntervalKind::Work, NOW - 1200, 1500),
+            record(IntervalKind::Pause, NOW - 60, 120),
+        ]);
+        assert_eq!(summary.today_work_secs, 3000);
+        assert_eq!(summary.today_break_secs, 300);
+        assert_eq!(summary.today_pause_secs, 120);
+        assert_eq!(summary.today_sessions, 2);
+        assert_eq!(summary.week_work_mins[STATS_DAYS - 1], 50);
+    }
+
+    #[test]
+    fn intervals_count_towards_the_day_they_ended_on() {
+        let midnight = 100 * SECONDS_PER_DAY;
+        let summary = summary(&[
+            // Started yesterday, ended today
+            record(IntervalKind::Work, midnight + 600, 1200),
+            // Ended a second before midnight
+            record(IntervalKind::Work, midnight - 1, 600),
+            record(IntervalKind::Break, midnight - 1, 300),
+        ]);
+        assert_eq!(summary.today_work_secs, 1200);
+        assert_eq!(summary.today_break_secs, 0);
+        assert_eq!(summary.today_sessions, 1);
+        assert_eq!(summary.week_work_mins[STATS_DAYS - 1], 20);
+        assert_eq!(summary.week_work_mins[STATS_DAYS - 2], 10);
+    }
+
+    #[test]
+    fn week_drops_the_eighth_day_back() {
+        let summary = summary(&[
+            record(IntervalKind::Work, NOW - 6 * SECONDS_PER_DAY, 600),
+            record(IntervalKind::Work, NOW - 7 * SECONDS_PER_DAY, 1200),
+        ]);
+        assert_eq!(summary.week_work_mins[0], 10);
+        assert_eq!(summary.week_work_mins.iter().sum::<u16>(), 10);
+        assert_eq!(summary.today_sessions, 0);
+    }
+
+    #[test]
+    fn future_records_are_skipped() {
+        let tomorrow = 101 * SECONDS_PER_DAY + 60;
+        let summary = summary(&[
+            record(IntervalKind::Work, tomorrow, 60),
+            record(IntervalKind::Break, tomorrow, 60),
+        ]);
+        assert_eq!(summary, StatsSummary::default());
+    }
+
+    #[test]
+    fn title_follows_the_clock() {
+        let title = |calendar_days| {
+            let scene = stats_scene(&StatsSummary::default().with_calendar_days(calendar_days));
+            scene.elements.iter().find_map(|element| match (element.id, &element.ui) {
+                (Some(STAT_TITLE), UIType::Label(label)) => Some(label.text() == "Today"),
+                _ => None,
+            })
+        };
+        assert_eq!(title(true), Some(true));
+        assert_eq!(title(false), Some(false));
+    }
+}
@@ -1,25 +1,187 @@
-use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, DrawTargetExt, Point, RgbColor, Size},
+    primitives::{PrimitiveStyleBuilder, Rectangle, StyledDrawable},
+    text::{Alignment, Baseline},
+    Drawable,
+};
+use embedded_graphics_framebuf::FrameBuf;
 
-use crate::scenes_util::UINode;
+use crate::{buffer_backend::BufferData, constants::LABEL_CAPACITY, glyph_atlas::{AtlasTextStyle, ATLAS}};
 
+// Height of the darker strip along the top of a text box
+const TITLE_BAR_HEIGHT: u32 = 10;
+// Gap between the text and the left/right edge of the box
+const TEXT_PADDING: i32 = 6;
+// Light grey box with a slightly darker title bar, 5/6/5 bits per channel
+const BOX_COLOR: Rgb565 = Rgb565::new(27, 55, 27);
+const TITLE_BAR_COLOR: Rgb565 = Rgb565::new(24, 47, 23);
+
+/*
+ * Box with a line of text in the Scientia-Black font,
+ * text that doesn't fit is clipped to the box.
+ * font_size picks the closest size baked into the glyph atlas.
+ */
 #[derive(Debug, Clone, Copy)]
 pub struct TextElement {
     pub position: Rectangle,
-    pub text: &'static str
+    pub text: &'static str,
+    pub font_size: u32,
+    pub color: Rgb565,
+    pub alignment: Alignment
 }
 
-impl UINode for DigitsElement {
-    fn get_position(&self) -> &Rectangle {
-        &self.position
+impl TextElement {
+    pub const fn new(position: Rectangle, text: &'static str) -> Self {
+        TextElement { position, text, font_size: 16, color: Rgb565::BLACK, alignment: Alignment::Left }
+    }
+
+    pub const fn with_font_size(self, font_size: u32) -> Self {
+        TextElement { font_size, ..self }
+    }
+
+    pub const fn with_color(self, color: Rgb565) -> Self {
+        TextElement { color, ..self }
     }
-    
-    fn handle_action(&mut self, _scene: &mut crate::scenes_util::SceneData, _action: crate::scenes_util::UIAction) {
+
+    pub const fn with_alignment(self, alignment: Alignment) -> Self {
+        TextElement { alignment, ..self }
+    }
+
+    // Area under the title bar the text is centred in
+    fn text_area(&self) -> Rectangle {
+        let bar = TITLE_BAR_HEIGHT.min(self.position.size.height);
+        Rectangle::new(
+            self.position.top_left + Point::new(0, bar as i32),
+            Size::new(self.position.size.width, self.position.size.height - bar)
+        )
+    }
+
+    // Where the text is anchored horizontally, the text style does the rest of the alignment
+    fn anchor(&self, area: &Rectangle) -> Point {
+        let center = area.center();
+        let x = match self.alignment {
+            Alignment::Left => area.top_left.x + TEXT_PADDING,
+            Alignment::Center => center.x,
+            Alignment::Right => area.top_left.x + area.size.width as i32 - 1 - TEXT_PADDING,
+        };
+        Point::new(x, center.y)
     }
 }
 
-pub struct DigitsElement {
-    pub position: Rectangle,
-    pub current_digit: u8,
-    next_element: u8,
-    prev_element: u8
+impl Drawable for TextElement {
+    type Color = Rgb565;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color> {
+        let background_style = PrimitiveStyleBuilder::new()
+            .fill_color(BOX_COLOR)
+            .stroke_width(2)
+            .stroke_color(Rgb565::BLACK)
+            .build();
+        self.position.draw_styled(&background_style, target)?;
+
+        let top_bar = Rectangle::new(
+            self.position.top_left,
+            Size::new(self.position.size.width, TITLE_BAR_HEIGHT)
+        );
+        let bar_style = PrimitiveStyleBuilder::new()
+            .fill_color(TITLE_BAR_COLOR)
+            .stroke_width(2)
+            .stroke_color(Rgb565::BLACK)
+            .build();
+        top_bar.draw_styled(&bar_style, target)?;
+
+        let Some(font) = ATLAS.font(self.font_size) else {
+            return Ok(());
+        };
+        let style = AtlasTextStyle::new(font, self.color)
+            .with_alignment(self.alignment)
+            .with_baseline(Baseline::Middle);
+
+        // The box is a solid colour, so glyph edges blend into it without reading the target back
+        let area = self.text_area();
+        style.draw_over(self.text, self.anchor(&area), BOX_COLOR, &mut target.clipped(&area))?;
+        Ok(())
+    }
+}
+
+const LABEL_FONT_SIZE: u32 = 16;
+
+/*
+ * Single line of text in the Scientia-Black font that can be filled in at runtime,
+ * give it an ElementId in the scene to find it again.
+ * Labels sit straight on the scene, so they're blended into the frame buffer
+ * by the renderer rather than drawn like the other elements.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct LabelElement {
+    pub position: Point,
+    pub color: Rgb565,
+    pub font_size: u32,
+    text: [u8; LABEL_CAPACITY],
+    len: u8
+}
+
+impl LabelElement {
+    pub const fn new(position: Point, text: &str, color: Rgb565) -> Self {
+        let bytes = text.as_bytes();
+        let mut buffer = [b' '; LABEL_CAPACITY];
+        let mut index = 0;
+        while index < bytes.len() && index < LABEL_CAPACITY {
+            buffer[index] = bytes[index];
+            index += 1;
+        }
+        LabelElement { position, color, font_size: LABEL_FONT_SIZE, text: buffer, len: index as u8 }
+    }
+
+    pub const fn with_font_size(self, font_size: u32) -> Self {
+        LabelElement { font_size, ..self }
+    }
+
+    fn style(&self) -> Option<AtlasTextStyle> {
+        ATLAS.font(self.font_size).map(|font| AtlasTextStyle::new(font, self.color))
+    }
+
+    // What the text covers now, a replaced label redraws the old and the new area
+    pub fn area(&self) -> Rectangle {
+        self.style()
+            .map_or(Rectangle::new(self.position, Size::zero()), |style| style.bounding_box(self.text(), self.position))
+    }
+
+    // Blends the text over whatever is in the frame buffer inside 'clip', returns the area it touched
+    pub fn blend(&self, frame_buffer: &mut FrameBuf<Rgb565, BufferData>, clip: &Rectangle) -> Rectangle {
+        match self.style() {
+            Some(style) => style.draw(self.text(), self.position, clip, frame_buffer),
+            None => Rectangle::zero()
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        str::from_utf8(&self.text[..self.len as usize]).unwrap_or("")
+    }
+
+    // Replace the text, anything past LABEL_CAPACITY bytes is cut off
+    pub fn set_fmt(&mut self, args: core::fmt::Arguments<'_>) {
+        self.len = 0;
+        let _ = core::fmt::Write::write_fmt(self, args);
+    }
+}
+
+impl core::fmt::Write for LabelElement {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for character in s.chars() {
+            let mut encoded = [0u8; 4];
+            let encoded = character.encode_utf8(&mut encoded).as_bytes();
+            let start = self.len as usize;
+            if start + encoded.len() > LABEL_CAPACITY {
+                break;
+            }
+            self.text[start..start + encoded.len()].copy_from_slice(encoded);
+            self.len += encoded.len() as u8;
+        }
+        Ok(())
+    }
 }
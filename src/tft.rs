@@ -17,7 +17,7 @@ use {
         static_cell::StaticCell,
         allocator_api2::boxed::Box,
         esp_alloc::ExternalMemory,
-        crate::constants::SPI_BUF_SIZE,
+        crate::constants::{PIXEL_COUNT, SPI_BUF_SIZE},
 };
 
 #[cfg(feature = "simulator")]
@@ -26,17 +26,18 @@ use {
 };
 
 use crate::{
-    animations::{Animation, FrameData, FrameType}, buffer_backend::BufferData, color_mixing::gradient::{Gradient, GradientDirection}, constants::{DISPLAY_HEIGHT, DISPLAY_WIDTH, EMPTY_SCENE, MAIN_MENU_SCENE, MAX_ANIMATIONS, PIXEL_COUNT, TEST_SCENE}, display_driver::DisplayDriver, payloads::SessionState, scenes_util::{SceneData, SceneManager}
+    animations::{Animation, FrameData, FrameType}, buffer_backend::BufferData, color_mixing::gradient::{Gradient, GradientDirection}, button::HoldEvent, constants::{DISPLAY_HEIGHT, DISPLAY_WIDTH, HOLD_ARC_AREA, MAX_ANIMATIONS, PAUSE_COLOR}, display_driver::DisplayDriver, overlay::{Overlays, DIALOG_AREA, TOAST_AREA}, progress::{ProgressRing, PROGRESS_MAX}, payloads::{BlockProgress, Cycle, OverlayEvent, SessionState, TimeUpdate}, scenes::{EMPTY_SCENE, MAIN_MENU_SCENE, SESSION_SCENE}, scenes_util::{ElementId, Scene, SceneData, SceneElement, SceneManager, UIAction, UIType}, stats::stats_scene, transitions::{Transition, TransitionState}
 };
 use crate::payloads::{Packet, Payload};
 
 use embedded_graphics::{
-    pixelcolor::PixelColor,
+    mono_font::{ascii::FONT_10X20, MonoTextStyle},
     pixelcolor::{ Rgb565, Rgb888 },
     prelude::*,
     primitives::{PrimitiveStyleBuilder, Rectangle, StrokeAlignment, StyledDrawable},
-    text::{Baseline, Text},
+    text::{renderer::TextRenderer, Alignment, Baseline, Text, TextStyleBuilder},
 };
+use core::fmt::Write;
 use embedded_graphics_framebuf::FrameBuf;
 use eg_seven_segment::SevenSegmentStyleBuilder;
 
@@ -89,7 +90,47 @@ where
     pub display: D,
     pub playing_animation: bool,
     frame_buffer: FrameBuf<Rgb565, BufferData>,
-    scene_manager: SceneManager
+    // Next scene is drawn here while a transition plays
+    incoming: FrameBuf<Rgb565, BufferData>,
+    transition: Option<TransitionState>,
+    scene_manager: SceneManager,
+    segmented: Option<SegmentedCache>,
+    // Where the focus highlight was last drawn
+    cursor: Option<Rectangle>,
+    overlays: Overlays,
+    // Last update of each timer on the session scene, an overlay coming down draws them again
+    times: [Option<TimeUpdate>; 3]
+}
+
+// Slot in TFT::times, one per timer on the session scene
+const fn time_slot(state: SessionState) -> usize {
+    match state {
+        SessionState::Working => 0,
+        SessionState::Break => 1,
+        _ => 2
+    }
+}
+
+/*
+ * Last string drawn by render_segmented,
+ * lets the next call redraw only the digits that changed
+ */
+#[derive(Clone, Copy)]
+struct SegmentedCache {
+    position: Point,
+    color: Rgb565,
+    message: [u8; 20],
+    len: usize
+}
+
+impl SegmentedCache {
+    fn new(position: Point, color: Rgb565, message: &str) -> Self {
+        let bytes = message.as_bytes();
+        let len = bytes.len().min(20);
+        let mut buffer = [b' '; 20];
+        buffer[..len].copy_from_slice(&bytes[..len]);
+        SegmentedCache { position, color, message: buffer, len }
+    }
 }
 
 #[cfg(feature = "simulator")]
@@ -100,12 +141,19 @@ impl TFT<SimulatorDisplay<Rgb565>> {
         // Heap-allocated framebuffer (no PSRAM on desktop)
         let buffer_data = BufferData::new_boxed();
         let frame_buffer = FrameBuf::new(buffer_data, DISPLAY_WIDTH as usize, DISPLAY_HEIGHT as usize);
+        let incoming = FrameBuf::new(BufferData::new_boxed(), DISPLAY_WIDTH as usize, DISPLAY_HEIGHT as usize);
 
         let mut tft = TFT {
             display,
             playing_animation: false,
             frame_buffer,
+            incoming,
+            transition: None,
             scene_manager: SceneManager::default(),
+            segmented: None,
+            cursor: None,
+            overlays: Overlays::default(),
+            times: [None; 3],
         };
         tft.initialize_scene();
         tft
@@ -157,6 +205,10 @@ impl<'spi> TFT<TFTDisplay<'spi>> {
         let boxed_buffer_data = BufferData::new(boxed_buffer_data);
         let frame_buffer = FrameBuf::new(boxed_buffer_data, DISPLAY_WIDTH as usize, DISPLAY_HEIGHT as usize);
 
+        // Second buffer for the scene a transition is heading to
+        let incoming_data: Box<[Rgb565; PIXEL_COUNT], ExternalMemory> = Box::new_in([Rgb565::BLACK; PIXEL_COUNT], ExternalMemory);
+        let incoming = FrameBuf::new(BufferData::new(incoming_data), DISPLAY_WIDTH as usize, DISPLAY_HEIGHT as usize);
+
         let mut display = Builder::new(ILI9488Rgb565, interface)
             .reset_pin(rst_output)
             .color_order(mipidsi::options::ColorOrder::Rgb)
@@ -172,7 +224,13 @@ impl<'spi> TFT<TFTDisplay<'spi>> {
             display,
             playing_animation: false,
             frame_buffer,
-            scene_manager: SceneManager::default()
+            incoming,
+            transition: None,
+            scene_manager: SceneManager::default(),
+            segmented: None,
+            cursor: None,
+            overlays: Overlays::default(),
+            times: [None; 3]
         };
         tft.initialize_scene();
         tft
@@ -185,27 +243,22 @@ where
     D::Error: core::fmt::Debug,
 {
     pub fn initialize_scene(&mut self) {
-        self.load_scene(EMPTY_SCENE);
+        // Whatever is in the frame buffer at boot isn't on screen, nothing to transition from
+        self.load_scene(SceneData { transition: Transition::Cut, ..EMPTY_SCENE });
     }
 
     pub fn handle_payload(&mut self, packet: &Packet) {
         let payload = packet.0;
+        // Anything new lands on the incoming scene, so it has to be fully on screen first
+        self.finish_transition();
 
         match payload {
             Payload::Menu => {
                 self.playing_animation = false;
                 self.load_scene(MAIN_MENU_SCENE);
             }
-            Payload::Time(bytes, state) => {
-                let message = str::from_utf8(&bytes).unwrap_or("error");
-
-                let ( color, point ) = match state {
-                    SessionState::Working => ( Rgb565::new(123, 191, 255), Point::new(10, 20) ),
-                    SessionState::Break => ( Rgb565::new(255, 148, 150), Point::new(10, 160) ),
-                    _ => ( Rgb565::WHITE, Point::new(10, 95) )
-                };
-                self.render_segmented(color, point, message);
-                // self.render_divider(state);
+            Payload::Time(update) => {
+                self.render_time(update);
             },
             Payload::Animate(animation) => {
                 // Only add the animation to the queue if there's space
@@ -225,26 +278,62 @@ where
                         self.render_next_frame();
                 }
             }
+            Payload::Stats(summary) => {
+                self.playing_animation = false;
+                self.load_scene(stats_scene(&summary));
+            }
             Payload::NewScene(new_scene) => {
-                self.scene_manager.initialize_scene(new_scene);
+                self.playing_animation = false;
+                self.load_scene(*new_scene);
+            }
+            Payload::Element(id, element) => {
+                self.replace_element(id, element);
             }
             _ => (),
         };
     }
 
-    pub fn load_scene(&mut self, scene: SceneData) {
+    fn background() -> Gradient {
         let end_color = Rgb565::from(Rgb888::new(39, 39, 39));
         let start_color = Rgb565::from(Rgb888::new(149, 149, 149));
-        let gradient = Gradient::new(start_color, end_color)
+        Gradient::new(start_color, end_color)
             .direction(GradientDirection::Vertical)
             .position(Point::zero())
-            .size(Size::new(320, 240));
-        gradient.draw(&mut self.frame_buffer).unwrap();
+            .size(Size::new(320, 240))
+    }
+
+    pub fn load_scene(&mut self, scene: SceneData) {
+        self.finish_transition();
+        self.segmented = None;
+        self.times = [None; 3];
+
+        if let Err(error) = self.scene_manager.initialize_scene(scene) {
+            debug_println!("scene {:?}: {:?}", scene.scene, error);
+        }
+
+        // With a transition to play the outgoing scene stays in the frame buffer for now
+        self.transition = TransitionState::start(scene.transition);
+        let target = match self.transition {
+            Some(_) => &mut self.incoming,
+            None => &mut self.frame_buffer
+        };
 
-        self.scene_manager.initialize_scene(scene);
+        Self::background().draw(target).unwrap();
+        let bounds = target.bounding_box();
+        Self::draw_elements(&self.scene_manager.current_scene.elements, target, &bounds);
+
+        // Focus starts on the scene's cursor_index, without sliding in
+        self.cursor = self.scene_manager.focused_rect();
+        if let Some(cursor) = self.cursor {
+            Self::draw_cursor(&cursor, target);
+        }
+        // A dialog or toast stays up across scene changes
+        self.overlays.draw(target).unwrap();
 
-        for element in self.scene_manager.current_scene.elements {
-            element.draw(&mut self.frame_buffer).unwrap();
+        if self.transition.is_some() {
+            // Sprites wait until the transition is done
+            self.playing_animation = true;
+            return;
         }
 
         self.display.fill_contiguous(
@@ -254,9 +343,165 @@ where
 
         let _ = self.frame_buffer.data.take_dirty_regions();
 
-        self.playing_animation = self.scene_manager.animation_queue.queue
+        self.playing_animation = self.frames_pending();
+    }
+
+    // Sprites still to play, or a toast waiting to be taken down
+    fn frames_pending(&self) -> bool {
+        self.overlays.toast.is_some() || self.scene_manager.animation_queue.queue
             .iter()
-            .any(|a| !matches!(a, Animation::Empty));
+            .any(|a| !matches!(a, Animation::Empty))
+    }
+
+    // Mix the next in-between frame straight onto the display, row by row
+    fn render_transition_frame(&mut self) {
+        let Some(mut transition) = self.transition else {
+            return;
+        };
+        let Some(frame) = transition.next_frame() else {
+            self.finish_transition();
+            return;
+        };
+        self.transition = Some(transition);
+
+        if frame.area.is_zero_sized() {
+            return;
+        }
+        let x0 = frame.area.top_left.x as u16;
+        let x1 = x0 + frame.area.size.width as u16 - 1;
+        for y in frame.area.rows() {
+            let pixels = (x0..=x1).map(|x| {
+                transition.pixel(&frame, &self.frame_buffer.data, &self.incoming.data, x as u32, y as u32)
+            });
+            self.display.set_pixel_region(x0, y as u16, x1, y as u16, pixels);
+        }
+    }
+
+    // Jump to the end of a running transition, the incoming scene becomes the frame buffer
+    fn finish_transition(&mut self) {
+        if self.transition.take().is_none() {
+            return;
+        }
+
+        core::mem::swap(&mut self.frame_buffer, &mut self.incoming);
+        self.display.fill_contiguous(
+            &self.display.bounding_box(), 
+            &self.frame_buffer.data
+        ).unwrap();
+        let _ = self.frame_buffer.data.take_dirty_regions();
+
+        self.playing_animation = self.frames_pending();
+    }
+
+    pub fn handle_overlay(&mut self, event: OverlayEvent) {
+        self.finish_transition();
+
+        match event {
+            OverlayEvent::Dialog(dialog) => {
+                self.overlays.dialog = Some(dialog);
+                self.transfer_region(&DIALOG_AREA);
+            }
+            OverlayEvent::Action(action) => {
+                if let Some(dialog) = &mut self.overlays.dialog {
+                    // Answering is up to the session, it closes the dialog
+                    dialog.step(action);
+                    self.transfer_region(&DIALOG_AREA);
+                } else {
+                    self.handle_action(action);
+                }
+            }
+            OverlayEvent::CloseDialog => {
+                if self.overlays.dialog.take().is_some() {
+                    self.close_overlay(&DIALOG_AREA);
+                    // Sprites were held back while the dialog was up
+                    self.playing_animation = self.frames_pending();
+                }
+            }
+            OverlayEvent::Toast(toast) => {
+                self.overlays.toast = Some(toast);
+                self.transfer_region(&TOAST_AREA);
+                self.playing_animation = true;
+            }
+        }
+    }
+
+    // Put back the scene under an overlay that was taken down
+    fn close_overlay(&mut self, area: &Rectangle) {
+        self.restore_background(area);
+        if let Some(cursor) = self.cursor {
+            Self::draw_cursor(&cursor, &mut self.frame_buffer.clipped(area));
+        }
+        self.transfer_region(area);
+
+        // The timers aren't part of the scene, draw them again from their last updates
+        self.segmented = None;
+        for update in self.times.into_iter().flatten() {
+            self.render_time(update);
+        }
+    }
+
+    fn tick_toast(&mut self) {
+        let Some(toast) = &mut self.overlays.toast else {
+            return;
+        };
+        if !toast.tick() {
+            self.overlays.toast = None;
+            self.close_overlay(&TOAST_AREA);
+        }
+    }
+
+    // Route an action to the focused element, redraw only what it changed
+    fn handle_action(&mut self, action: UIAction) {
+        let outcome = self.scene_manager.handle_action(action);
+
+        if let Some(area) = outcome.redraw {
+            self.restore_background(&area);
+            // Focus stayed put, the highlight goes back on top of the redrawn element
+            if let (None, Some(cursor)) = (outcome.focus_moved, self.cursor) {
+                Self::draw_cursor(&cursor, &mut self.frame_buffer.clipped(&area));
+            }
+            self.transfer_region(&area);
+        }
+
+        if outcome.focus_moved.is_some() {
+            self.playing_animation = true;
+            self.render_next_frame();
+        }
+    }
+
+    // Redraw just the area the old and new versions of the element cover
+    fn replace_element(&mut self, id: ElementId, element: UIType) {
+        let Some(area) = self.scene_manager.replace_element(id, element) else {
+            return;
+        };
+        let area = area.intersection(&self.display.bounding_box());
+        if area.is_zero_sized() {
+            return;
+        }
+
+        self.restore_background(&area);
+        if let Some(cursor) = self.cursor {
+            Self::draw_cursor(&cursor, &mut self.frame_buffer.clipped(&area));
+        }
+        self.transfer_region(&area);
+    }
+
+    // Redraw the scene underneath an overlay that's going away
+    fn restore_background(&mut self, area: &Rectangle) {
+        Self::background().draw(&mut self.frame_buffer.clipped(area)).unwrap();
+        Self::draw_elements(&self.scene_manager.current_scene.elements, &mut self.frame_buffer, area);
+    }
+
+    // The scene's elements inside 'area', in order. Labels blend over what's under them already
+    fn draw_elements(elements: &[SceneElement], target: &mut FrameBuf<Rgb565, BufferData>, area: &Rectangle) {
+        for element in elements {
+            match &element.ui {
+                UIType::Label(label) => {
+                    label.blend(target, area);
+                }
+                ui => ui.draw(&mut target.clipped(area)).unwrap(),
+            }
+        }
     }
 
     pub fn flush_dirty_regions(&mut self) {
@@ -272,6 +517,11 @@ where
     }
 
     fn transfer_region(&mut self, rect: &Rectangle) {
+        // Whatever changed underneath, overlays stay on top
+        if self.overlays.covers(rect) {
+            self.overlays.draw(&mut self.frame_buffer.clipped(rect)).unwrap();
+        }
+
         let x0 = rect.top_left.x as u16;
         let y0 = rect.top_left.y as u16;
         let x1 = x0 + rect.size.width as u16 - 1;
@@ -292,6 +542,17 @@ where
     }
 
     pub fn render_next_frame(&mut self) {
+        if self.transition.is_some() {
+            self.render_transition_frame();
+            return;
+        }
+
+        self.tick_toast();
+        // Sprites go straight to the display and would end up on top of the dialog
+        if self.overlays.dialog.is_some() {
+            self.playing_animation = self.overlays.toast.is_some();
+            return;
+        }
 
         // Grab array of frames to be rendered
         let frame_queue = self.scene_manager.play_next();
@@ -307,7 +568,6 @@ where
                     // Only flush dirty regions
                     self.flush_dirty_regions();
                     self.animate_cursor(rect);
-                    self.frame_buffer.data.mark_dirty(rect);
                 },
                 FrameType::Sprite(frame_data) => { 
                     self.render_frame(frame_data);
@@ -317,10 +577,16 @@ where
         }
 
         // Turn off 30 fps render flag if no more frames in the queue
-        if empty_count == MAX_ANIMATIONS { self.playing_animation = false };
+        if empty_count == MAX_ANIMATIONS && self.overlays.toast.is_none() { self.playing_animation = false };
     }
 
     fn render_frame(&mut self, frame_data: FrameData) {
+        // Skipped under a toast rather than drawn over it
+        let area = Rectangle::new(frame_data.position, Size::new(frame_data.width as u32, frame_data.height as u32));
+        if self.overlays.covers(&area) {
+            return;
+        }
+
         let x0 = frame_data.position.x as u16;
         let y0 = frame_data.position.y as u16;
         let x1 = x0 + frame_data.width - 1;
@@ -335,42 +601,195 @@ where
     }
 
     fn animate_cursor(&mut self, cursor: Rectangle) {
+        // Wipe the highlight from the previous frame, only under its own rectangle
+        let previous = self.cursor.replace(cursor);
+        if let Some(previous) = previous {
+            self.restore_background(&previous);
+        }
+
+        // Update buffer data with a new cursor and position
+        Self::draw_cursor(&cursor, &mut self.frame_buffer);
+        
+        // Draw the buffer to display with the cursor
+        if let Some(previous) = previous {
+            self.transfer_region(&previous);
+        }
+        self.transfer_region(&cursor);
+    }
+
+    fn draw_cursor<T: DrawTarget<Color = Rgb565>>(cursor: &Rectangle, target: &mut T) {
         let cursor_style = PrimitiveStyleBuilder::new()
             .stroke_color(Rgb565::new(154, 153, 150))
             .stroke_width(2)
             .stroke_alignment(StrokeAlignment::Inside)
             .build();
 
-        // Update buffer data with a new cursor and position
-        cursor
-            .draw_styled(&cursor_style, &mut self.frame_buffer)
-            .unwrap();
-        
-        // Draw the buffer to display with the cursor
-        self.transfer_region(&cursor);
+        let _ = cursor.draw_styled(&cursor_style, target);
     }
 
     #[inline]
     pub fn render_segmented(&mut self, color: Rgb565, position: Point, message: &str) {
-        // Reset the buffer to black, but don't draw to the screen yet
-        let draw_area = Rectangle::new(position, Size::new(300, 50));
-        let _ = &mut self.frame_buffer.fill_solid(&draw_area, Rgb565::BLACK).unwrap();
-
+        // Fractional seconds need narrower digits to fit on screen
+        let (digit_width, digit_spacing) = if message.len() > 8 { (22, 8) } else { (30, 10) };
         let style = SevenSegmentStyleBuilder::new()
-            .digit_size(Size::new(30, 50))
-            .digit_spacing(10)
+            .digit_size(Size::new(digit_width, 50))
+            .digit_spacing(digit_spacing)
             .segment_width(5)
             .segment_color(color)
             .build();
 
-        let text = Text::with_baseline(message, position, style, Baseline::Top);
+        let previous = self.segmented
+            .filter(|cache| cache.position == position && cache.color == color && cache.len == message.len());
+        self.segmented = Some(SegmentedCache::new(position, color, message));
+
+        let Some(previous) = previous else {
+            // Reset the buffer to black, but don't draw to the screen yet
+            let draw_area = Rectangle::new(position, Size::new(300, 50));
+            let _ = &mut self.frame_buffer.fill_solid(&draw_area, Rgb565::BLACK).unwrap();
+
+            let text = Text::with_baseline(message, position, style, Baseline::Top);
+
+            // Write time pixel data to the buffer
+            let _ = text.draw(&mut self.frame_buffer).unwrap();
+
+            // Finally, draw the buffer to the screen
+            self.transfer_region(&draw_area);
+            return;
+        };
+
+        // Same layout as last time: only push runs of digits that changed,
+        // at tenths/hundredths this keeps the SPI bus mostly idle
+        let old = previous.message;
+        let new = message.as_bytes();
+        let mut index = 0;
+        while index < new.len() {
+            if old[index] == new[index] {
+                index += 1;
+                continue;
+            }
+
+            let start = index;
+            while index < new.len() && old[index] != new[index] {
+                index += 1;
+            }
+
+            let x0 = style.measure_string(&message[..start], position, Baseline::Top).next_position.x;
+            let x1 = style.measure_string(&message[..index], position, Baseline::Top).next_position.x;
+            let changed_area = Rectangle::new(
+                Point::new(x0, position.y),
+                Size::new((x1 - x0) as u32, 50)
+            );
+            let _ = &mut self.frame_buffer.fill_solid(&changed_area, Rgb565::BLACK).unwrap();
+
+            let text = Text::with_baseline(&message[start..index], changed_area.top_left, style, Baseline::Top);
+            let _ = text.draw(&mut self.frame_buffer).unwrap();
+
+            self.transfer_region(&changed_area);
+        }
+    }
+
+    // Timers, cycle counter and adjustment of a running session
+    fn render_time(&mut self, update: TimeUpdate) {
+        // First update of a session, put the timers' own scene behind them
+        if self.scene_manager.current_scene.scene != Scene::Session {
+            self.playing_animation = false;
+            self.load_scene(SESSION_SCENE);
+        }
+        self.times[time_slot(update.state)] = Some(update);
+        if let Some(progress) = update.progress {
+            self.render_progress(progress);
+        }
 
-        // Write time pixel data to the buffer
+        let message = str::from_utf8(&update.digits).unwrap_or("error").trim_end();
+
+        let ( color, point ) = match update.state {
+            SessionState::Working => ( Rgb565::new(123, 191, 255), Point::new(10, 20) ),
+            SessionState::Break => ( Rgb565::new(255, 148, 150), Point::new(10, 160) ),
+            SessionState::Paused => ( PAUSE_COLOR, Point::new(10, 95) ),
+            _ => ( Rgb565::WHITE, Point::new(10, 95) )
+        };
+        self.render_segmented(color, point, message);
+        if let Some(cycle) = update.cycle {
+            self.render_cycle(color, point, cycle);
+        }
+        if update.state != SessionState::Paused {
+            self.render_adjustment(color, point, update.adjusted_mins);
+        }
+    }
+
+    // Move the scene's progress widgets along, each redraws only what it changed
+    pub fn render_progress(&mut self, progress: BlockProgress) {
+        let permille = progress.permille();
+        for element in self.scene_manager.current_scene.elements.iter_mut() {
+            match &mut element.ui {
+                UIType::ProgressRing(ring) => ring.advance(permille, &mut self.frame_buffer.data),
+                UIType::ProgressBar(bar) => bar.advance(permille, &mut self.frame_buffer.data),
+                _ => {}
+            }
+        }
+        self.flush_dirty_regions();
+    }
+
+    // Small "current/total" label tucked under the right end of the segmented time
+    pub fn render_cycle(&mut self, color: Rgb565, position: Point, cycle: Cycle) {
+        let draw_area = Rectangle::new(position + Point::new(240, 52), Size::new(60, 20));
+        let _ = &mut self.frame_buffer.fill_solid(&draw_area, Rgb565::BLACK).unwrap();
+
+        let mut message: heapless::String<8> = heapless::String::new();
+        let _ = write!(message, "{}/{}", cycle.current, cycle.total);
+
+        let character_style = MonoTextStyle::new(&FONT_10X20, color);
+        let text_style = TextStyleBuilder::new()
+            .alignment(Alignment::Right)
+            .baseline(Baseline::Top)
+            .build();
+        let anchor = Point::new(draw_area.top_left.x + draw_area.size.width as i32, draw_area.top_left.y);
+        let text = Text::with_text_style(&message, anchor, character_style, text_style);
         let _ = text.draw(&mut self.frame_buffer).unwrap();
 
-        // Finally, draw the buffer to the screen
         self.transfer_region(&draw_area);
+    }
+
+    // Ring that fills up while the button is held, so a short press can be told from a long one
+    pub fn render_hold(&mut self, event: HoldEvent) {
+        self.finish_transition();
+        let area = HOLD_ARC_AREA;
+        // Whatever the scene has there goes back first, the ring is blended over it
+        self.restore_background(&area);
+        let progress = match event {
+            HoldEvent::Started => Some(0),
+            HoldEvent::Repeat(progress) => Some(progress),
+            HoldEvent::Completed | HoldEvent::Cancelled => None
+        };
+
+        if let Some(progress) = progress {
+            let ring = ProgressRing::new(area.center(), area.size.width / 2 - 1, Rgb565::WHITE)
+                .with_thickness(3)
+                .with_progress(progress as u16 * PROGRESS_MAX / 100);
+            ring.blend_over(&mut self.frame_buffer.data);
+        }
+        if let Some(cursor) = self.cursor {
+            Self::draw_cursor(&cursor, &mut self.frame_buffer.clipped(&area));
+        }
+        self.frame_buffer.data.mark_dirty(area);
+        self.flush_dirty_regions();
+    }
+
+    // "+5m" / "-2m" under the left end of the segmented time after an encoder correction
+    pub fn render_adjustment(&mut self, color: Rgb565, position: Point, minutes: i16) {
+        let draw_area = Rectangle::new(position + Point::new(0, 52), Size::new(80, 20));
+        let _ = &mut self.frame_buffer.fill_solid(&draw_area, Rgb565::BLACK).unwrap();
 
+        if minutes != 0 {
+            let mut message: heapless::String<8> = heapless::String::new();
+            let _ = write!(message, "{:+}m", minutes);
+
+            let character_style = MonoTextStyle::new(&FONT_10X20, color);
+            let text = Text::with_baseline(&message, draw_area.top_left, character_style, Baseline::Top);
+            let _ = text.draw(&mut self.frame_buffer).unwrap();
+        }
+
+        self.transfer_region(&draw_area);
     }
 }
 
@@ -1,107 +1,480 @@
 use core::ops::AddAssign;
 use embassy_time::{Duration, Instant};
 
+use crate::clock_source::{ClockSource, EmbassyClock};
+use crate::payloads::{BlockProgress, Cycle, SessionState};
+
+#[derive(Clone, Copy)]
 struct SingleTime {
     last_update: Instant,
     seconds_running: Duration,
+    // Time spent in the current countdown block, reset whenever a new block starts
+    block_running: Duration,
+    // Net manual correction in seconds, shown next to the timer
+    adjusted: i32,
     is_running: bool
 }
 
+impl SingleTime {
+    fn new(now: Instant, is_running: bool) -> Self {
+        SingleTime {
+            last_update: now,
+            seconds_running: Duration::from_secs(0),
+            block_running: Duration::from_secs(0),
+            adjusted: 0,
+            is_running
+        }
+    }
+
+    // Start the timer if needed, then add the time elapsed since the last update
+    fn update(&mut self, now: Instant) {
+        if !self.is_running {
+            self.last_update = now;
+            self.is_running = true;
+        }
+        let elapsed = now - self.last_update;
+        self.seconds_running += elapsed;
+        self.block_running += elapsed;
+        self.last_update = now;
+    }
+
+    // Move the timer forward or back, never below zero
+    fn adjust(&mut self, adjust: TimerAdjust) {
+        let before = self.seconds_running;
+        match adjust {
+            TimerAdjust::Forward(by) => {
+                self.seconds_running += by;
+                self.block_running += by;
+            }
+            TimerAdjust::Back(by) => {
+                let zero = Duration::from_secs(0);
+                self.seconds_running = self.seconds_running.checked_sub(by).unwrap_or(zero);
+                self.block_running = self.block_running.checked_sub(by).unwrap_or(zero);
+            }
+        }
+        self.adjusted += self.seconds_running.as_secs() as i32 - before.as_secs() as i32;
+    }
+
+    // Account for the time since the last update before stopping
+    fn stop(&mut self, now: Instant) {
+        if self.is_running {
+            self.update(now);
+            self.is_running = false;
+        }
+    }
+}
+
+// Timer state right before the last switch between work, break and pause
+#[derive(Clone, Copy)]
+struct Snapshot {
+    work_time: SingleTime,
+    break_time: SingleTime,
+    pause_time: SingleTime,
+    active_block: SessionState,
+    cycle: u8,
+    paused: bool
+}
+
+/*
+ * Lengths of a single pomodoro ("Taro") cycle
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaroConfig {
+    pub work_block: Duration,
+    pub break_block: Duration,
+}
+
+impl TaroConfig {
+    // 25 minutes of work, 5 minutes of break
+    pub const DEFAULT: Self = TaroConfig {
+        work_block: Duration::from_secs(25 * 60),
+        break_block: Duration::from_secs(5 * 60),
+    };
+}
+
+impl Default for TaroConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/*
+ * Pomodoro cycle with a long break after every 'cycles' work blocks ("Taro Plus")
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaroPlusConfig {
+    pub work_block: Duration,
+    pub break_block: Duration,
+    pub long_break_block: Duration,
+    pub cycles: u8,
+}
+
+impl TaroPlusConfig {
+    // Four 25/5 cycles, then a 15 minute break
+    pub const DEFAULT: Self = TaroPlusConfig {
+        work_block: Duration::from_secs(25 * 60),
+        break_block: Duration::from_secs(5 * 60),
+        long_break_block: Duration::from_secs(15 * 60),
+        cycles: 4,
+    };
+}
+
+impl Default for TaroPlusConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TimerMode {
+    // Work and break timers count up until switched manually
+    #[default]
+    CountingUp,
+    // Work and break blocks count down and switch automatically
+    Taro(TaroConfig),
+    // Like Taro, but every n-th break is a long one
+    TaroPlus(TaroPlusConfig),
+}
+
+// Step used by the encoder when correcting a running timer
+pub const ADJUST_STEP: Duration = Duration::from_secs(60);
+
+// Manual correction of a timer, e.g. when it was started late
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimerAdjust {
+    Forward(Duration),
+    Back(Duration),
+}
+
+// How finely the running timer is displayed (and how often device_loop ticks)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TimerResolution {
+    // HH:MM:SS
+    #[default]
+    Seconds,
+    // HH:MM:SS.t
+    Tenths,
+    // HH:MM:SS.hh
+    Hundredths,
+}
+
+impl TimerResolution {
+    // Seconds -> tenths -> hundredths -> seconds
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Seconds => Self::Tenths,
+            Self::Tenths => Self::Hundredths,
+            Self::Hundredths => Self::Seconds,
+        }
+    }
+
+    pub const fn tick(self) -> Duration {
+        Duration::from_millis(self.tick_ms())
+    }
+
+    const fn tick_ms(self) -> u64 {
+        match self {
+            Self::Seconds => 1000,
+            Self::Tenths => 100,
+            Self::Hundredths => 10,
+        }
+    }
+
+    const fn fraction_digits(self) -> usize {
+        match self {
+            Self::Seconds => 0,
+            Self::Tenths => 1,
+            Self::Hundredths => 2,
+        }
+    }
+}
+
 // Duration to be rendered on display
-pub struct Time {
+pub struct Time<C: ClockSource = EmbassyClock> {
+    clock: C,
     offset: Duration,
     work_time: SingleTime,
     break_time: SingleTime,
-    paused: bool
+    // Interruptions, counts up for the whole session
+    pause_time: SingleTime,
+    // Which timer the current countdown block belongs to
+    active_block: SessionState,
+    // 1-based index of the current work block within a Taro Plus set
+    cycle: u8,
+    mode: TimerMode,
+    resolution: TimerResolution,
+    paused: bool,
+    // Lets a single accidental switch be undone
+    last_switch: Option<Snapshot>
 }
 
-impl Default for Time {
+impl<C: ClockSource + Default> Default for Time<C> {
     fn default() -> Self {
-        let work_time = SingleTime {
-            last_update: Instant::now(),
-            seconds_running: Duration::from_secs(0),
-            is_running: true
-        };
+        Self::with_clock(C::default(), TimerMode::default())
+    }
+}
 
-        let break_time = SingleTime {
-            last_update: Instant::now(),
-            seconds_running: Duration::from_secs(0),
-            is_running: false
-        };
+impl Time {
+    pub fn new(mode: TimerMode) -> Self {
+        Self::with_clock(EmbassyClock, mode)
+    }
+}
 
+impl<C: ClockSource> Time<C> {
+    pub fn with_clock(clock: C, mode: TimerMode) -> Self {
+        let now = clock.now();
         Self {
+            clock,
             offset: Duration::from_millis(0),
-            work_time,
-            break_time,
-            paused: false
+            work_time: SingleTime::new(now, true),
+            break_time: SingleTime::new(now, false),
+            pause_time: SingleTime::new(now, false),
+            active_block: SessionState::MainMenu,
+            cycle: 1,
+            mode,
+            resolution: TimerResolution::default(),
+            paused: false,
+            last_switch: None
         }
     }
-}
 
-impl Time {
+    // Start a fresh session, keeping the mode and resolution
+    pub fn restart(&mut self) {
+        let now = self.clock.now();
+        self.offset = Duration::from_millis(0);
+        self.work_time = SingleTime::new(now, true);
+        self.break_time = SingleTime::new(now, false);
+        self.pause_time = SingleTime::new(now, false);
+        self.active_block = SessionState::MainMenu;
+        self.cycle = 1;
+        self.paused = false;
+        self.last_switch = None;
+    }
+
+    #[inline]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    #[inline]
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn set_mode(&mut self, mode: TimerMode) {
+        self.mode = mode;
+        self.cycle = 1;
+        self.last_switch = None;
+    }
+
+    #[inline]
+    pub fn resolution(&self) -> TimerResolution {
+        self.resolution
+    }
+
+    #[inline]
+    pub fn set_resolution(&mut self, resolution: TimerResolution) {
+        self.resolution = resolution;
+    }
+
+    // Position within the current set of work blocks, only tracked in Taro Plus mode
+    pub fn cycle(&self) -> Option<Cycle> {
+        match self.mode {
+            TimerMode::TaroPlus(config) => Some(Cycle {
+                current: self.cycle,
+                total: config.cycles
+            }),
+            _ => None
+        }
+    }
+
+    // Length of the countdown block for the given state, None when counting up
+    pub fn block_target(&self, state: SessionState) -> Option<Duration> {
+        match (self.mode, state) {
+            (TimerMode::Taro(config), SessionState::Working) => Some(config.work_block),
+            (TimerMode::Taro(config), SessionState::Break) => Some(config.break_block),
+            (TimerMode::TaroPlus(config), SessionState::Working) => Some(config.work_block),
+            (TimerMode::TaroPlus(config), SessionState::Break) => {
+                // The break that follows the last work block of a set is the long one
+                if self.cycle >= config.cycles {
+                    Some(config.long_break_block)
+                } else {
+                    Some(config.break_block)
+                }
+            }
+            _ => None
+        }
+    }
+
+    // Time spent in the countdown block against its length, None when counting up.
+    // While paused this is the block that was interrupted.
+    pub fn block_progress(&self, state: SessionState) -> Option<BlockProgress> {
+        let state = if state == SessionState::Paused { self.active_block } else { state };
+        let elapsed = match state {
+            SessionState::Working => self.work_time.block_running,
+            SessionState::Break => self.break_time.block_running,
+            _ => return None
+        };
+        self.block_target(state).map(|target| BlockProgress {
+            elapsed: elapsed.as_secs() as u32,
+            target: target.as_secs() as u32
+        })
+    }
+
     #[inline]
     pub fn now(&self) -> Duration {
-        let ms = Instant::now().as_millis() + self.offset.as_millis();
+        let ms = self.clock.now().as_millis() + self.offset.as_millis();
         Duration::from_millis(ms)
     }
 
-    // NOTE: This should take another argument of type SessionState.
-    //       Update individual Self Duration fields based on this.
-    //       Paused shouldn't increment?
     #[inline]
     pub fn sleep_for_work(&mut self) -> ([u8; 20], Duration) {
+        self.record_switch(SessionState::Working);
         let now = self.now();
-        let sleep_duration = Self::until_next(now, Duration::from_secs(1));
+        let sleep_duration = Self::until_next(now, self.resolution.tick());
 
-        self.break_time.is_running = false;
+        self.break_time.stop(self.clock.now());
+        self.pause_time.stop(self.clock.now());
         self.paused = false;
-        if !self.work_time.is_running {
-            self.work_time.last_update = Instant::now();
-            self.work_time.is_running = true;
+
+        // Resuming from a pause continues the block, switching from a break starts a new one
+        if self.active_block != SessionState::Working {
+            if self.active_block == SessionState::Break {
+                self.next_cycle();
+            }
+            self.work_time.block_running = Duration::from_secs(0);
+            self.active_block = SessionState::Working;
         }
-        let elapsed = Instant::now() - self.work_time.last_update;
-        self.work_time.seconds_running += elapsed;
-        self.work_time.last_update = Instant::now();
+        self.work_time.update(self.clock.now());
 
-        let seconds_now = self.work_time.seconds_running.as_secs();
-        let hours = seconds_now / 3600;
-        let minutes = (seconds_now % 3600) / 60;
-        let seconds = seconds_now % 60;
+        let shown_ms = match self.block_target(SessionState::Working) {
+            None => elapsed_ms(self.work_time.seconds_running, self.resolution),
+            Some(target) => remaining_ms(target, self.work_time.block_running, self.resolution)
+        };
 
-        let time_arr = format_time(hours, minutes, seconds);
+        let time_arr = format_millis(shown_ms, self.resolution);
         ( time_arr, sleep_duration )
     }
 
     #[inline]
     pub fn sleep_for_break(&mut self) -> ([u8; 20], Duration) {
+        self.record_switch(SessionState::Break);
         let now = self.now();
-        let sleep_duration = Self::until_next(now, Duration::from_secs(1));
+        let sleep_duration = Self::until_next(now, self.resolution.tick());
 
-        self.work_time.is_running = false;
+        self.work_time.stop(self.clock.now());
+        self.pause_time.stop(self.clock.now());
         self.paused = false;
-        if !self.break_time.is_running {
-            self.break_time.last_update = Instant::now();
-            self.break_time.is_running = true;
+
+        if self.active_block != SessionState::Break {
+            self.break_time.block_running = Duration::from_secs(0);
+            self.active_block = SessionState::Break;
         }
-        let elapsed = Instant::now() - self.break_time.last_update;
-        self.break_time.seconds_running += elapsed;
-        self.break_time.last_update = Instant::now();
+        self.break_time.update(self.clock.now());
 
-        let seconds_now = self.break_time.seconds_running.as_secs();
-        let hours = seconds_now / 3600;
-        let minutes = (seconds_now % 3600) / 60;
-        let seconds = seconds_now % 60;
+        let shown_ms = match self.block_target(SessionState::Break) {
+            None => elapsed_ms(self.break_time.seconds_running, self.resolution),
+            Some(target) => remaining_ms(target, self.break_time.block_running, self.resolution)
+        };
 
-        let time_arr = format_time(hours, minutes, seconds);
+        let time_arr = format_millis(shown_ms, self.resolution);
         ( time_arr, sleep_duration )
     }
 
     #[inline]
     pub fn sleep_for_pause(&mut self) -> ([u8; 20], Duration) {
-        self.work_time.is_running = false;
-        self.break_time.is_running = false;
+        self.record_switch(SessionState::Paused);
+        let now = self.now();
+        let sleep_duration = Self::until_next(now, self.resolution.tick());
+
+        // The countdown block is left as is and continues on resume
+        let clock_now = self.clock.now();
+        self.work_time.stop(clock_now);
+        self.break_time.stop(clock_now);
+        self.pause_time.update(clock_now);
         self.paused = true;
-        ([0; 20], Duration::from_secs(1))
+
+        let shown_ms = elapsed_ms(self.pause_time.seconds_running, self.resolution);
+        let time_arr = format_millis(shown_ms, self.resolution);
+        ( time_arr, sleep_duration )
+    }
+
+    // Total time spent paused since the session started
+    #[inline]
+    pub fn paused_total(&self) -> Duration {
+        self.pause_time.seconds_running
+    }
+
+    // Apply a correction to the timer shown in the given state.
+    // While paused this is the work or break timer that was interrupted.
+    pub fn adjust(&mut self, state: SessionState, adjust: TimerAdjust) {
+        let state = if state == SessionState::Paused { self.active_block } else { state };
+        match state {
+            SessionState::Working => self.work_time.adjust(adjust),
+            SessionState::Break => self.break_time.adjust(adjust),
+            _ => {}
+        }
+    }
+
+    // Net correction applied to a timer so far, in whole minutes
+    pub fn adjusted_mins(&self, state: SessionState) -> i16 {
+        match state {
+            SessionState::Working => (self.work_time.adjusted / 60) as i16,
+            SessionState::Break => (self.break_time.adjusted / 60) as i16,
+            _ => 0
+        }
+    }
+
+    // True once the countdown block of the given state has run out.
+    // Always false when counting up.
+    pub fn block_expired(&self, state: SessionState) -> bool {
+        match (self.block_target(state), state) {
+            (Some(target), SessionState::Working) => self.work_time.block_running >= target,
+            (Some(target), SessionState::Break) => self.break_time.block_running >= target,
+            _ => false
+        }
+    }
+
+    // Go back to the timer that was running before the last switch.
+    // The time since then is credited to it, as if the switch never happened.
+    // Returns the restored state, None if there is nothing to undo.
+    pub fn undo_switch(&mut self) -> Option<SessionState> {
+        let snapshot = self.last_switch.take()?;
+        self.work_time = snapshot.work_time;
+        self.break_time = snapshot.break_time;
+        self.pause_time = snapshot.pause_time;
+        self.active_block = snapshot.active_block;
+        self.cycle = snapshot.cycle;
+        self.paused = snapshot.paused;
+        Some(self.current_state())
+    }
+
+    fn current_state(&self) -> SessionState {
+        if self.paused { SessionState::Paused } else { self.active_block }
+    }
+
+    // Called before any timer changes, so the snapshot still has the old timer running
+    fn record_switch(&mut self, to: SessionState) {
+        let from = self.current_state();
+        if from == to || from == SessionState::MainMenu {
+            return;
+        }
+        self.last_switch = Some(Snapshot {
+            work_time: self.work_time,
+            break_time: self.break_time,
+            pause_time: self.pause_time,
+            active_block: self.active_block,
+            cycle: self.cycle,
+            paused: self.paused
+        });
+    }
+
+    // Advance to the next work block, wrapping back to 1 after a long break
+    fn next_cycle(&mut self) {
+        if let TimerMode::TaroPlus(config) = self.mode {
+            self.cycle = if self.cycle >= config.cycles { 1 } else { self.cycle + 1 };
+        }
     }
 
     #[inline]
@@ -111,13 +484,48 @@ impl Time {
     }
 }
 
-impl AddAssign<Duration> for Time {
+impl<C: ClockSource> AddAssign<Duration> for Time<C> {
     fn add_assign(&mut self, rhs: Duration) {
         let ms = self.offset.as_millis() + rhs.as_millis();
         self.offset = Duration::from_millis(ms)
     }
 }
 
+// Elapsed time rounded down to the displayed resolution
+fn elapsed_ms(elapsed: Duration, resolution: TimerResolution) -> u64 {
+    let tick = resolution.tick_ms();
+    elapsed.as_millis() / tick * tick
+}
+
+// Time left in a block, rounded up so "00:00:00" only shows once it has expired
+fn remaining_ms(block: Duration, elapsed: Duration, resolution: TimerResolution) -> u64 {
+    let tick = resolution.tick_ms();
+    let remaining = block.as_millis().saturating_sub(elapsed.as_millis());
+    remaining.div_ceil(tick) * tick
+}
+
+fn format_millis(ms: u64, resolution: TimerResolution) -> [u8; 20] {
+    let seconds_now = ms / 1000;
+    let hours = seconds_now / 3600;
+    let minutes = (seconds_now % 3600) / 60;
+    let seconds = seconds_now % 60;
+
+    let mut buffer = format_time(hours, minutes, seconds);
+
+    // Optional ".t" / ".hh" after the seconds
+    let digits = resolution.fraction_digits();
+    if digits > 0 {
+        let fraction = ms % 1000;
+        buffer[8] = b'.';
+        let mut divisor = 100;
+        for digit in buffer[9..9 + digits].iter_mut() {
+            *digit = (fraction / divisor % 10) as u8 + b'0';
+            divisor /= 10;
+        }
+    }
+    buffer
+}
+
 fn format_time(hours: u64, mins: u64, seconds: u64) -> [u8; 20] {
     let mut buffer = [b' '; 20]; // Initialize with spaces
     
@@ -129,7 +537,7 @@ fn format_time(hours: u64, mins: u64, seconds: u64) -> [u8; 20] {
     let s1 = (seconds / 10) as u8 + b'0';
     let s2 = (seconds % 10) as u8 + b'0';
     
-    // Format: "HH:MM:SS.hh"
+    // Format: "HH:MM:SS" (fraction digits are added by format_millis)
     buffer[0] = h1;
     buffer[1] = h2;
     buffer[2] = b':';
@@ -140,3 +548,139 @@ fn format_time(hours: u64, mins: u64, seconds: u64) -> [u8; 20] {
     buffer[7] = s2;
     buffer
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock_source::FakeClock;
+
+    fn text(digits: &[u8; 20]) -> &str {
+        core::str::from_utf8(digits).unwrap().trim_end()
+    }
+
+    fn taro() -> TimerMode {
+        TimerMode::Taro(TaroConfig::DEFAULT)
+    }
+
+    #[test]
+    fn counting_up_starts_at_zero_and_follows_the_clock() {
+        let clock = FakeClock::new();
+        let mut time = Time::with_clock(&clock, TimerMode::CountingUp);
+
+        assert_eq!(text(&time.sleep_for_work().0), "00:00:00");
+        clock.advance(Duration::from_secs(3725));
+        assert_eq!(text(&time.sleep_for_work().0), "01:02:05");
+    }
+
+    #[test]
+    fn pause_freezes_the_work_timer() {
+        let clock = FakeClock::new();
+        let mut time = Time::with_clock(&clock, TimerMode::CountingUp);
+        time.sleep_for_work();
+        clock.advance(Duration::from_secs(90));
+
+        assert_eq!(text(&time.sleep_for_pause().0), "00:00:00");
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(text(&time.sleep_for_pause().0), "00:00:30");
+        assert_eq!(time.paused_total(), Duration::from_secs(30));
+
+        // Picks up where it stopped, the pause isn't counted as work
+        assert_eq!(text(&time.sleep_for_work().0), "00:01:30");
+    }
+
+    #[test]
+    fn switching_to_a_break_starts_a_new_block() {
+        let clock = FakeClock::new();
+        let mut time = Time::with_clock(&clock, taro());
+        time.sleep_for_work();
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(text(&time.sleep_for_work().0), "00:24:00");
+
+        assert_eq!(text(&time.sleep_for_break().0), "00:05:00");
+        clock.advance(Duration::from_secs(61));
+        assert_eq!(text(&time.sleep_for_break().0), "00:03:59");
+
+        // Back to work is a fresh 25 minutes
+        assert_eq!(text(&time.sleep_for_work().0), "00:25:00");
+    }
+
+    #[test]
+    fn blocks_expire_at_their_length() {
+        let clock = FakeClock::new();
+        let mut time = Time::with_clock(&clock, taro());
+        time.sleep_for_work();
+
+        clock.advance(Duration::from_secs(25 * 60 - 1));
+        time.sleep_for_work();
+        assert!(!time.block_expired(SessionState::Working));
+        assert_eq!(time.block_progress(SessionState::Working), Some(BlockProgress { elapsed: 25 * 60 - 1, target: 25 * 60 }));
+
+        clock.advance(Duration::from_secs(1));
+        let (digits, _) = time.sleep_for_work();
+        assert!(time.block_expired(SessionState::Working));
+        assert_eq!(text(&digits), "00:00:00");
+    }
+
+    #[test]
+    fn counting_up_never_expires() {
+        let clock = FakeClock::new();
+        let mut time = Time::with_clock(&clock, TimerMode::CountingUp);
+        clock.advance(Duration::from_secs(24 * 3600));
+        time.sleep_for_work();
+        assert!(!time.block_expired(SessionState::Working));
+        assert_eq!(time.block_progress(SessionState::Working), None);
+    }
+
+    #[test]
+    fn taro_plus_ends_a_set_with_the_long_break() {
+        let clock = FakeClock::new();
+        let config = TaroPlusConfig { cycles: 2, ..TaroPlusConfig::DEFAULT };
+        let mut time = Time::with_clock(&clock, TimerMode::TaroPlus(config));
+
+        time.sleep_for_work();
+        assert_eq!(time.cycle(), Some(Cycle { current: 1, total: 2 }));
+        assert_eq!(time.block_target(SessionState::Break), Some(config.break_block));
+
+        time.sleep_for_break();
+        time.sleep_for_work();
+        assert_eq!(time.cycle(), Some(Cycle { current: 2, total: 2 }));
+        assert_eq!(time.block_target(SessionState::Break), Some(config.long_break_block));
+
+        time.sleep_for_break();
+        time.sleep_for_work();
+        assert_eq!(time.cycle(), Some(Cycle { current: 1, total: 2 }));
+    }
+
+    #[test]
+    fn undo_gives_the_time_back_to_the_previous_timer() {
+        let clock = FakeClock::new();
+        let mut time = Time::with_clock(&clock, TimerMode::CountingUp);
+        time.sleep_for_work();
+        clock.advance(Duration::from_secs(60));
+        time.sleep_for_break();
+        clock.advance(Duration::from_secs(10));
+
+        assert_eq!(time.undo_switch(), Some(SessionState::Working));
+        assert_eq!(text(&time.sleep_for_work().0), "00:01:10");
+        assert_eq!(time.undo_switch(), None);
+    }
+
+    #[test]
+    fn until_next_lines_up_with_the_tick() {
+        let second = Duration::from_secs(1);
+        assert_eq!(Time::<FakeClock>::until_next(Duration::from_millis(0), second), second);
+        assert_eq!(Time::<FakeClock>::until_next(Duration::from_millis(250), second), Duration::from_millis(750));
+        assert_eq!(Time::<FakeClock>::until_next(Duration::from_millis(1999), second), Duration::from_millis(1));
+
+        let tenth = TimerResolution::Tenths.tick();
+        assert_eq!(Time::<FakeClock>::until_next(Duration::from_millis(1234), tenth), Duration::from_millis(66));
+    }
+
+    #[test]
+    fn sleep_is_measured_from_the_clock() {
+        let clock = FakeClock::new();
+        let mut time = Time::with_clock(&clock, TimerMode::CountingUp);
+        clock.advance(Duration::from_millis(400));
+        assert_eq!(time.sleep_for_work().1, Duration::from_millis(600));
+    }
+}
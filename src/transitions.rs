@@ -0,0 +1,100 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{Point, Size},
+    primitives::Rectangle,
+};
+
+use crate::{
+    buffer_backend::BufferData,
+    color_mixing::alpha_blending::RGBa,
+    constants::{DISPLAY_HEIGHT, DISPLAY_WIDTH},
+};
+
+// Frames between the outgoing and incoming scene, at FRAME_RATE
+pub const TRANSITION_FRAMES: u8 = 6;
+
+// How a scene replaces the one on screen
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Transition {
+    // Straight swap, no frames in between
+    #[default]
+    Cut,
+    // Incoming scene pushes the old one out to the left
+    Slide,
+    // Cross-fade between the two
+    Fade,
+    // Incoming scene is uncovered left to right
+    Wipe,
+}
+
+/*
+ * A transition in progress. The outgoing scene stays in the frame buffer and
+ * the incoming one is drawn offscreen, every frame is mixed from the two on its way to the display.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct TransitionState {
+    transition: Transition,
+    frame_index: u8,
+}
+
+// One in-between frame: how far along it is, and the part of the screen that changed
+#[derive(Debug, Clone, Copy)]
+pub struct TransitionFrame {
+    pub progress: u8,
+    pub area: Rectangle,
+}
+
+impl TransitionState {
+    // Nothing to play for a cut
+    pub fn start(transition: Transition) -> Option<Self> {
+        match transition {
+            Transition::Cut => None,
+            _ => Some(TransitionState { transition, frame_index: 0 }),
+        }
+    }
+
+    // None once the incoming scene should be shown as is
+    pub fn next_frame(&mut self) -> Option<TransitionFrame> {
+        self.frame_index += 1;
+        if self.frame_index >= TRANSITION_FRAMES {
+            return None;
+        }
+
+        let progress = Self::progress_at(self.frame_index);
+        let area = match self.transition {
+            // Only the band uncovered since the last frame
+            Transition::Wipe => {
+                let from = Self::edge(Self::progress_at(self.frame_index - 1));
+                Rectangle::new(Point::new(from as i32, 0), Size::new(Self::edge(progress) - from, DISPLAY_HEIGHT))
+            }
+            _ => Rectangle::new(Point::zero(), Size::new(DISPLAY_WIDTH, DISPLAY_HEIGHT)),
+        };
+        Some(TransitionFrame { progress, area })
+    }
+
+    const fn progress_at(frame_index: u8) -> u8 {
+        (frame_index as u32 * 255 / TRANSITION_FRAMES as u32) as u8
+    }
+
+    // Columns of the screen 'progress' covers
+    const fn edge(progress: u8) -> u32 {
+        DISPLAY_WIDTH * progress as u32 / 255
+    }
+
+    // Pixel at x, y on the display for this frame
+    pub fn pixel(&self, frame: &TransitionFrame, outgoing: &BufferData, incoming: &BufferData, x: u32, y: u32) -> Rgb565 {
+        match self.transition {
+            Transition::Slide => {
+                let x = x + Self::edge(frame.progress);
+                if x < DISPLAY_WIDTH {
+                    outgoing.pixel(x, y)
+                } else {
+                    incoming.pixel(x - DISPLAY_WIDTH, y)
+                }
+            }
+            Transition::Fade => RGBa::new(incoming.pixel(x, y), frame.progress).blend(outgoing.pixel(x, y)),
+            Transition::Wipe if x < Self::edge(frame.progress) => incoming.pixel(x, y),
+            Transition::Wipe | Transition::Cut => outgoing.pixel(x, y),
+        }
+    }
+}